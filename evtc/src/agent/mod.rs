@@ -25,3 +25,9 @@ pub use self::{
     affinity::*, agent_kind::*, attack_target::*, breakbar::*, combat::*, glider::*, health::*,
     id::*, status::*, stunbreak::*, targetable::*, team::*,
 };
+
+/// Strips the `':'` prefix from an account name if present.
+#[inline]
+pub fn strip_account_prefix(account_name: &str) -> &str {
+    account_name.strip_prefix(':').unwrap_or(account_name)
+}
@@ -387,11 +387,36 @@ impl From<Event> for EventKind {
                 StateChange::Transformation => Self::Transformation(event.extract()),
                 StateChange::WvwTeams => Self::WvwTeams(event.extract()),
                 StateChange::WvwObjectiveStatus => Self::WvwObjectiveStatus(event.extract()),
-                StateChange::IdleEvent | StateChange::ReplInfo | StateChange::EarlyExit => {
-                    unreachable!("illegal internal statechange")
-                }
-                StateChange::Unknown(_) => Self::Unknown(event),
+                // internal statechanges should never appear, treat as unknown
+                StateChange::IdleEvent
+                | StateChange::ReplInfo
+                | StateChange::EarlyExit
+                | StateChange::Unknown(_) => Self::Unknown(event),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_statechange() {
+        for statechange in [
+            StateChange::IdleEvent,
+            StateChange::ReplInfo,
+            StateChange::EarlyExit,
+        ] {
+            let event = Event {
+                time: 123,
+                is_statechange: statechange.into(),
+                ..Event::default()
+            };
+            assert!(
+                matches!(EventKind::from(event), EventKind::Unknown(event) if event.time == 123),
+                "{statechange:?} not unknown"
+            );
+        }
+    }
+}
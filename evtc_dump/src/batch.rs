@@ -0,0 +1,346 @@
+use crate::{
+    dump::DumpOptions,
+    error,
+    format::Format,
+    inspect::Summary,
+    stdio::{self, Input},
+};
+use clap::error::ErrorKind;
+use serde::Serialize;
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs,
+    io::{self, BufWriter},
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+/// Batch arguments.
+#[derive(Debug, Clone, clap::Args)]
+pub struct Batch {
+    /// Input directory or glob pattern of EVTC files.
+    ///
+    /// Directories are searched recursively for `.evtc` and `.zevtc` files.
+    pub input: String,
+
+    /// Output directory for dump files.
+    ///
+    /// Defaults to next to the input files.
+    #[clap(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Write a combined NDJSON index of log summaries instead of dumps, `-` for stdout.
+    ///
+    /// Summaries cover the full logs, so dump options can not be used.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "output", "data", "format", "kinds", "sources", "targets", "skills", "start", "end"
+        ]
+    )]
+    pub index: Option<PathBuf>,
+
+    /// Amount of logs to process in parallel.
+    ///
+    /// Defaults to the available parallelism.
+    #[clap(long, short)]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Dump options.
+    #[clap(flatten)]
+    pub options: DumpOptions,
+}
+
+/// Entry of the batch index.
+#[derive(Debug, Clone, Serialize)]
+struct IndexEntry {
+    path: PathBuf,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+
+    #[serde(flatten)]
+    summary: Option<Summary>,
+}
+
+impl Batch {
+    /// Processes all logs.
+    pub fn run(&self) {
+        if stdio::is_stdio(Path::new(&self.input)) {
+            error(
+                ErrorKind::InvalidValue,
+                "batch input has to be a directory or glob pattern",
+            );
+        }
+        if self.output.as_deref().is_some_and(stdio::is_stdio) {
+            error(
+                ErrorKind::InvalidValue,
+                "batch dumps can not be written to stdout, use --index - for summaries",
+            );
+        }
+        if self.index.is_none() {
+            self.options.validate();
+        }
+
+        let (root, files) = match self.find_files() {
+            Ok(found) => found,
+            Err(err) => error(ErrorKind::InvalidValue, err),
+        };
+
+        // keep stdout clean for the index
+        let to_stdout = self.index.as_deref().is_some_and(stdio::is_stdio);
+        let status = |message: String| stdio::status(message, to_stdout);
+        status(format!("Found {} logs", files.len()));
+
+        let outputs = match &self.index {
+            Some(_) => HashMap::new(),
+            None => match self.output_paths(&root, &files) {
+                Ok(outputs) => outputs,
+                Err(err) => error(ErrorKind::ValueValidation, err),
+            },
+        };
+
+        let results = self.process_all(&files, status, |path| match &self.index {
+            Some(_) => stdio::parse(path)
+                .map(|log| Some(Summary::new(log)))
+                .map_err(|err| err.to_string()),
+            None => self.dump(path, &outputs[path]).map(|()| None),
+        });
+
+        let failed = results.iter().filter(|entry| entry.error.is_some()).count();
+        if let Some(path) = &self.index {
+            if let Err(err) = stdio::create(path)
+                .and_then(|file| Format::Ndjson.write_entries(BufWriter::new(file), &[], &results))
+            {
+                stdio::exit_on_broken_pipe(&err);
+                error(ErrorKind::Io, format!("failed to write index: {err}"));
+            }
+            status(format!("Wrote index to {}", stdio::name(path, "stdout")));
+        }
+        status(format!("Processed {} logs, {failed} failed", results.len()));
+
+        if failed > 0 {
+            std::process::exit(1);
+        }
+    }
+
+    /// Finds input files, returning the root directory of the search.
+    fn find_files(&self) -> Result<(PathBuf, Vec<PathBuf>), String> {
+        let path = Path::new(&self.input);
+        let (root, mut files) = if path.is_dir() {
+            let mut files = Vec::new();
+            walk(path, &mut files).map_err(|err| format!("failed to read directory: {err}"))?;
+            (path.to_path_buf(), files)
+        } else {
+            let paths = glob::glob(&self.input).map_err(|err| err.to_string())?;
+            let files = paths
+                .filter_map(Result::ok)
+                .filter(|path| is_log(path))
+                .collect();
+            (glob_root(&self.input), files)
+        };
+        if files.is_empty() {
+            return Err(format!("no logs found for \"{}\"", self.input));
+        }
+        files.sort();
+        Ok((root, files))
+    }
+
+    /// Returns the output paths for the files.
+    ///
+    /// Fails if multiple files would be dumped to the same output path.
+    fn output_paths(
+        &self,
+        root: &Path,
+        files: &[PathBuf],
+    ) -> Result<HashMap<PathBuf, PathBuf>, String> {
+        let mut inputs = HashMap::<PathBuf, &Path>::new();
+        let mut outputs = HashMap::new();
+        for path in files {
+            let output = match &self.output {
+                Some(dir) => {
+                    let relative = path.strip_prefix(root).unwrap_or(path);
+                    self.options.output_path(&dir.join(relative))
+                }
+                None => self.options.output_path(path),
+            };
+            if let Some(other) = inputs.insert(output.clone(), path) {
+                return Err(format!(
+                    "\"{}\" and \"{}\" would both be dumped to \"{}\"",
+                    other.display(),
+                    path.display(),
+                    output.display()
+                ));
+            }
+            outputs.insert(path.clone(), output);
+        }
+        Ok(outputs)
+    }
+
+    /// Processes the files in parallel.
+    ///
+    /// Panics while processing a file are reported as failure of the file.
+    fn process_all(
+        &self,
+        files: &[PathBuf],
+        status: impl Fn(String) + Sync,
+        process: impl Fn(&Path) -> Result<Option<Summary>, String> + Sync,
+    ) -> Vec<IndexEntry> {
+        let jobs = self
+            .jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+            .min(files.len());
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(files.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                scope.spawn(|| {
+                    while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| process(path)))
+                            .unwrap_or_else(|payload| Err(panic_message(payload)));
+                        let entry = match result {
+                            Ok(summary) => {
+                                status(format!("Processed \"{}\"", path.display()));
+                                IndexEntry {
+                                    path: path.clone(),
+                                    error: None,
+                                    summary,
+                                }
+                            }
+                            Err(err) => {
+                                eprintln!("Failed \"{}\": {err}", path.display());
+                                IndexEntry {
+                                    path: path.clone(),
+                                    error: Some(err),
+                                    summary: None,
+                                }
+                            }
+                        };
+                        results.lock().expect("poisoned results").push(entry);
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().expect("poisoned results");
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+        results
+    }
+
+    /// Dumps a single log to the output path.
+    fn dump(&self, path: &Path, output: &Path) -> Result<(), String> {
+        let input = Input::open(path).map_err(|err| err.to_string())?;
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        self.options
+            .save(input, output)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Returns the message of a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("panicked: {message}")
+}
+
+/// Checks whether the path has an EVTC file extension.
+fn is_log(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("evtc") || ext.eq_ignore_ascii_case("zevtc")
+        })
+}
+
+/// Recursively collects log files in the directory.
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if is_log(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the directory before the first glob pattern component.
+fn glob_root(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dump::Data, filter::Filter};
+
+    #[test]
+    fn root() {
+        assert_eq!(
+            glob_root("logs/arcdps.cbtlogs/*/2023*.zevtc"),
+            Path::new("logs/arcdps.cbtlogs")
+        );
+        assert_eq!(glob_root("logs/log.evtc"), Path::new("logs"));
+        assert_eq!(glob_root("*.evtc"), Path::new(""));
+    }
+
+    #[test]
+    fn panic() {
+        let batch = Batch {
+            input: String::new(),
+            output: None,
+            index: None,
+            jobs: NonZeroUsize::new(2),
+            options: DumpOptions {
+                data: Data::default(),
+                format: Format::default(),
+                filter: Filter::default(),
+            },
+        };
+        let files = [PathBuf::from("a.evtc"), PathBuf::from("b.evtc")];
+        let results = batch.process_all(
+            &files,
+            |_| {},
+            |path| {
+                if path == Path::new("b.evtc") {
+                    panic!("invalid log");
+                }
+                Ok(None)
+            },
+        );
+        assert_eq!(
+            results
+                .iter()
+                .map(|entry| entry.error.as_deref())
+                .collect::<Vec<_>>(),
+            [None, Some("panicked: invalid log")]
+        );
+    }
+}
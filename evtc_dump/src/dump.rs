@@ -0,0 +1,250 @@
+use crate::{
+    error,
+    filter::Filter,
+    format::{self, Format},
+    stdio::{self, Input},
+};
+use clap::{ValueEnum, error::ErrorKind};
+use evtc_parse::{Agent, Event, EventKind, Header, LogReader, ParseError, Skill};
+use std::{
+    fmt,
+    io::{self, BufWriter, Read},
+    path::{Path, PathBuf},
+};
+use strum::Display;
+
+/// Dump arguments.
+#[derive(Debug, Clone, clap::Args)]
+pub struct Dump {
+    /// Input path to EVTC file or `-` for stdin.
+    pub input: PathBuf,
+
+    /// Output path to dump file or `-` for stdout.
+    ///
+    /// Defaults to input path with file extension of the format.
+    /// Defaults to stdout when reading from stdin.
+    pub output: Option<PathBuf>,
+
+    /// Dump options.
+    #[clap(flatten)]
+    pub options: DumpOptions,
+}
+
+impl Dump {
+    /// Returns the path to the output file.
+    fn output_path(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| {
+            if stdio::is_stdio(&self.input) {
+                PathBuf::from(stdio::STDIO)
+            } else {
+                self.options.output_path(&self.input)
+            }
+        })
+    }
+
+    /// Dumps the log data.
+    pub fn run(&self) {
+        self.options.validate();
+
+        let input = &self.input;
+        let output = self.output_path();
+
+        // keep stdout clean for the dumped data
+        let status = |message: String| stdio::status(message, stdio::is_stdio(&output));
+
+        status(format!("Parsing {}...", stdio::name(input, "stdin")));
+
+        let header = match Input::open(input)
+            .map_err(DumpError::from)
+            .and_then(|input| self.options.save(input, &output))
+        {
+            Ok(header) => header,
+            Err(DumpError::Filter(err)) => error(ErrorKind::InvalidValue, err),
+            Err(DumpError::Io(err)) => {
+                stdio::exit_on_broken_pipe(&err);
+                error(ErrorKind::Io, DumpError::Io(err))
+            }
+            Err(err) => error(ErrorKind::Io, err),
+        };
+
+        status(format!(
+            "Parsed {} log for encounter id {}",
+            header.date, header.boss_id
+        ));
+        status(format!(
+            "Dumped {} data to {}",
+            self.options.data,
+            stdio::name(&output, "stdout")
+        ));
+    }
+}
+
+/// Options for dumping log data.
+#[derive(Debug, Clone, clap::Args)]
+pub struct DumpOptions {
+    /// Data to dump.
+    #[clap(value_enum, long, short, default_value_t)]
+    pub data: Data,
+
+    /// Output format.
+    ///
+    /// NDJSON and CSV require agents, skills or events data.
+    /// CSV events contain the raw event fields.
+    #[clap(value_enum, long, short, default_value_t)]
+    pub format: Format,
+
+    /// Event filters.
+    #[clap(flatten)]
+    pub filter: Filter,
+}
+
+impl DumpOptions {
+    /// Exits if the options conflict.
+    pub fn validate(&self) {
+        if matches!(self.data, Data::All) && self.format.is_tabular() {
+            error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "{} format requires agents, skills or events data",
+                    self.format
+                ),
+            );
+        }
+    }
+
+    /// Returns the default output path for the input path.
+    pub fn output_path(&self, input: &Path) -> PathBuf {
+        input.with_extension(self.format.extension())
+    }
+
+    /// Saves the log data from the input to the output file or stdout, returning the log header.
+    pub fn save(&self, input: Input, path: &Path) -> Result<Header, DumpError> {
+        match input {
+            Input::Evtc(input) => self.save_from(LogReader::new(input)?, path),
+            Input::Zevtc(mut archive) => self.save_from(LogReader::from_zevtc(&mut archive)?, path),
+        }
+    }
+
+    /// Saves the log data from the reader.
+    ///
+    /// Events are written while they are read, other data requires the full log.
+    fn save_from(
+        &self,
+        mut reader: LogReader<impl Read>,
+        path: &Path,
+    ) -> Result<Header, DumpError> {
+        let columns = self.data.columns();
+        if !matches!(self.data, Data::Events) {
+            let mut log = reader.into_log()?;
+            self.filter.apply(&mut log).map_err(DumpError::Filter)?;
+            let log = log.into_transformed();
+            let writer = BufWriter::new(stdio::create(path)?);
+            match self.data {
+                Data::Agents => self.format.write_entries(writer, &columns, &log.agents)?,
+                Data::Skills => self.format.write_entries(writer, &columns, &log.skills)?,
+                _ => self.format.write(writer, &log)?,
+            }
+            return Ok(log.header);
+        }
+
+        let mut filter = self
+            .filter
+            .resolve(&reader.agents, &reader.skills)
+            .map_err(DumpError::Filter)?;
+        let writer = BufWriter::new(stdio::create(path)?);
+        let mut result = Ok(());
+        let events = reader
+            .by_ref()
+            .map_while(|event| event.map_err(|err| result = Err(err)).ok())
+            .filter(|event| filter.matches(event));
+        if self.format == Format::Csv {
+            self.format.write_entries(writer, &columns, events)?;
+        } else {
+            self.format
+                .write_entries(writer, &columns, events.map(EventKind::from))?;
+        }
+        result?;
+        Ok(reader.header)
+    }
+}
+
+/// Error while dumping a log.
+#[derive(Debug)]
+pub enum DumpError {
+    /// Failed to parse the log.
+    Parse(ParseError),
+
+    /// Filters did not match the log.
+    Filter(String),
+
+    /// Failed to write the output.
+    Io(io::Error),
+}
+
+impl From<ParseError> for DumpError {
+    #[inline]
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<io::Error> for DumpError {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "failed to parse EVTC log: {err}"),
+            Self::Filter(err) => write!(f, "{err}"),
+            Self::Io(err) => write!(f, "failed to write data: {err}"),
+        }
+    }
+}
+
+/// Data to dump.
+#[derive(Debug, Display, Default, Clone, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Data {
+    /// All log data.
+    #[default]
+    All,
+
+    /// Log agents.
+    Agents,
+
+    /// Skill & buff information.
+    Skills,
+
+    /// Log events.
+    Events,
+}
+
+impl Data {
+    /// Returns the fixed CSV columns of the data.
+    pub fn columns(&self) -> Vec<String> {
+        match self {
+            Self::All => Vec::new(),
+            Self::Agents => format::columns(&Agent {
+                id: 0,
+                name: vec![String::new(); 3],
+                profession: 0,
+                is_elite: 0,
+                hitbox_width: 0,
+                hitbox_height: 0,
+                toughness: 0,
+                concentration: 0,
+                healing: 0,
+                condition: 0,
+            }),
+            Self::Skills => format::columns(&Skill {
+                id: 0,
+                name: String::new(),
+            }),
+            Self::Events => format::columns(&Event::default()),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use evtc_parse::{Agent, Event, Log, Skill, StateChange, agent::strip_account_prefix};
+use std::collections::HashSet;
+use strum::VariantNames;
+
+/// Event filters.
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct Filter {
+    /// Only dump events of the given kind or state change, e.g. BuffApply.
+    #[clap(long = "kind", value_name = "NAME", value_parser = parse_kind)]
+    pub kinds: Vec<&'static str>,
+
+    /// Only dump events with the given source agent id, account or character name.
+    #[clap(long = "source", value_name = "AGENT")]
+    pub sources: Vec<String>,
+
+    /// Only dump events with the given target agent id, account or character name.
+    #[clap(long = "target", value_name = "AGENT")]
+    pub targets: Vec<String>,
+
+    /// Only dump events with the given skill id or name.
+    #[clap(long = "skill", value_name = "SKILL")]
+    pub skills: Vec<String>,
+
+    /// Only dump events after the given milliseconds since log start.
+    #[clap(long, value_name = "MS")]
+    pub start: Option<u64>,
+
+    /// Only dump events before the given milliseconds since log start.
+    #[clap(long, value_name = "MS")]
+    pub end: Option<u64>,
+}
+
+impl Filter {
+    /// Whether no filters are set.
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+            && self.sources.is_empty()
+            && self.targets.is_empty()
+            && self.skills.is_empty()
+            && self.start.is_none()
+            && self.end.is_none()
+    }
+
+    /// Removes all events from the log not matching the filters.
+    pub fn apply(&self, log: &mut Log) -> Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let mut filter = self.resolve(&log.agents, &log.skills)?;
+        log.events.retain(|event| filter.matches(event));
+        Ok(())
+    }
+
+    /// Resolves the filters for the agents and skills of a log.
+    pub fn resolve(&self, agents: &[Agent], skills: &[Skill]) -> Result<EventFilter<'_>, String> {
+        Ok(EventFilter {
+            filter: self,
+            kinds: self.kinds.iter().copied().collect(),
+            sources: Self::resolve_agents(agents, &self.sources)?,
+            targets: Self::resolve_agents(agents, &self.targets)?,
+            skills: Self::resolve_skills(skills, &self.skills)?,
+            log_start: None,
+        })
+    }
+
+    /// Resolves agent ids matching the given ids or names.
+    fn resolve_agents(agents: &[Agent], values: &[String]) -> Result<Option<HashSet<u64>>, String> {
+        if values.is_empty() {
+            return Ok(None);
+        }
+        let mut ids = HashSet::new();
+        for value in values {
+            let account = strip_account_prefix(value);
+            let len = ids.len();
+            ids.extend(
+                agents
+                    .iter()
+                    .filter(|agent| {
+                        value.parse() == Ok(agent.id)
+                            || agent.name.first().is_some_and(|name| name == value)
+                            || agent.account_name() == Some(account)
+                    })
+                    .map(|agent| agent.id),
+            );
+            if ids.len() == len {
+                return Err(format!("no agent matching \"{value}\""));
+            }
+        }
+        Ok(Some(ids))
+    }
+
+    /// Resolves skill ids matching the given ids or names.
+    fn resolve_skills(skills: &[Skill], values: &[String]) -> Result<Option<HashSet<u32>>, String> {
+        if values.is_empty() {
+            return Ok(None);
+        }
+        let mut ids = HashSet::new();
+        for value in values {
+            if let Ok(id) = value.parse() {
+                ids.insert(id);
+                continue;
+            }
+            let len = ids.len();
+            ids.extend(
+                skills
+                    .iter()
+                    .filter(|skill| skill.name.eq_ignore_ascii_case(value))
+                    .map(|skill| skill.id),
+            );
+            if ids.len() == len {
+                return Err(format!("no skill matching \"{value}\""));
+            }
+        }
+        Ok(Some(ids))
+    }
+}
+
+/// Event filters resolved for a log.
+#[derive(Debug, Clone)]
+pub struct EventFilter<'a> {
+    filter: &'a Filter,
+    kinds: HashSet<&'static str>,
+    sources: Option<HashSet<u64>>,
+    targets: Option<HashSet<u64>>,
+    skills: Option<HashSet<u32>>,
+    log_start: Option<u64>,
+}
+
+impl EventFilter<'_> {
+    /// Checks whether the event matches the filters.
+    ///
+    /// Events have to be passed in log order.
+    /// Time ranges are relative to the first timestamped event.
+    /// Events without timestamp are not affected by time ranges.
+    pub fn matches(&mut self, event: &Event) -> bool {
+        if event.has_time() {
+            self.log_start.get_or_insert(event.time);
+        }
+        (self.kinds.is_empty() || self.kinds.contains(kind_name(event)))
+            && self
+                .sources
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&event.src_agent))
+            && self
+                .targets
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&event.dst_agent))
+            && self
+                .skills
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&event.skill_id))
+            && (!event.has_time() || {
+                let time = event
+                    .time
+                    .saturating_sub(self.log_start.unwrap_or_default());
+                self.filter.start.is_none_or(|start| time >= start)
+                    && self.filter.end.is_none_or(|end| time <= end)
+            })
+    }
+}
+
+/// [`EventKind`](evtc_parse::EventKind) names differing from their [`StateChange`] name.
+const KIND_ALIASES: &[(&str, &str)] = &[("AgentMarker", "Marker")];
+
+/// Parses a case-insensitive event kind or state change name into its [`StateChange`] name.
+fn parse_kind(value: &str) -> Result<&'static str, String> {
+    KIND_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(value))
+        .map(|(_, name)| *name)
+        .or_else(|| {
+            StateChange::VARIANTS
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(value))
+                .copied()
+        })
+        .ok_or_else(|| format!("unknown event kind \"{value}\""))
+}
+
+/// Returns the [`StateChange`] name of the event.
+#[inline]
+fn kind_name(event: &Event) -> &'static str {
+    event.get_statechange().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evtc_parse::{Agent, Skill, StateChange, fixture};
+
+    fn log() -> Log {
+        let event = |time, src_agent, skill_id, statechange| Event {
+            dst_agent: 3,
+            skill_id,
+            ..fixture::statechange(time, src_agent, statechange)
+        };
+        Log {
+            skills: vec![Skill {
+                id: 740,
+                name: "Might".into(),
+            }],
+            ..fixture::log(
+                123,
+                vec![
+                    fixture::agent(1, &["Character", ":Account.1234", "1"]),
+                    Agent {
+                        profession: 2,
+                        ..fixture::agent(2, &["Other", ":Other.5678", "1"])
+                    },
+                ],
+                vec![
+                    event(1000, 1, 740, StateChange::BuffApply),
+                    event(1500, 2, 740, StateChange::BuffApply),
+                    event(2000, 1, 5, StateChange::Combat),
+                    event(5000, 1, 740, StateChange::BuffApply),
+                ],
+            )
+        }
+    }
+
+    #[test]
+    fn filter() {
+        let mut log = log();
+        Filter {
+            kinds: vec![parse_kind("buffapply").expect("unknown kind")],
+            sources: vec!["Account.1234".into()],
+            skills: vec!["might".into()],
+            end: Some(3000),
+            ..Filter::default()
+        }
+        .apply(&mut log)
+        .expect("failed to apply filter");
+        assert_eq!(
+            log.events
+                .iter()
+                .map(|event| event.time)
+                .collect::<Vec<_>>(),
+            [1000]
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        let result = Filter {
+            targets: vec!["Nobody".into()],
+            ..Filter::default()
+        }
+        .apply(&mut log());
+        assert_eq!(result, Err("no agent matching \"Nobody\"".into()));
+        assert_eq!(parse_kind("agentmarker"), Ok("Marker"));
+        assert_eq!(
+            parse_kind("Nothing"),
+            Err("unknown event kind \"Nothing\"".into())
+        );
+    }
+}
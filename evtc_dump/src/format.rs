@@ -0,0 +1,220 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+use strum::Display;
+
+/// Output format.
+#[derive(Debug, Display, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Format {
+    /// Pretty-printed JSON.
+    #[default]
+    Pretty,
+
+    /// Compact JSON.
+    Json,
+
+    /// Newline-delimited JSON, one entry per line.
+    Ndjson,
+
+    /// Comma-separated values, one entry per row.
+    Csv,
+}
+
+impl Format {
+    /// Returns the file extension for the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Pretty | Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// Whether the format requires a list of entries.
+    pub fn is_tabular(self) -> bool {
+        matches!(self, Self::Ndjson | Self::Csv)
+    }
+
+    /// Writes a single value.
+    ///
+    /// Tabular formats write the value as single entry.
+    pub fn write(self, writer: impl Write, data: &impl Serialize) -> io::Result<()> {
+        if self.is_tabular() {
+            self.write_entries(writer, &columns(data), [data])
+        } else {
+            self.write_json(writer, data)
+        }
+    }
+
+    /// Writes a list of entries.
+    ///
+    /// Tabular formats write entries as they are produced.
+    /// CSV uses the given columns, other formats ignore them.
+    pub fn write_entries<T: Serialize>(
+        self,
+        mut writer: impl Write,
+        columns: &[String],
+        entries: impl IntoIterator<Item = T>,
+    ) -> io::Result<()> {
+        match self {
+            Self::Pretty | Self::Json => {
+                let entries = entries.into_iter().collect::<Vec<_>>();
+                return self.write_json(writer, &entries);
+            }
+            Self::Ndjson => {
+                for entry in entries {
+                    serde_json::to_writer(&mut writer, &entry)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            Self::Csv => write_csv(&mut writer, columns, entries)?,
+        }
+        writer.flush()
+    }
+
+    /// Writes a value as JSON.
+    fn write_json(self, mut writer: impl Write, data: &impl Serialize) -> io::Result<()> {
+        if self == Self::Pretty {
+            serde_json::to_writer_pretty(&mut writer, data)?;
+        } else {
+            serde_json::to_writer(&mut writer, data)?;
+        }
+        writer.flush()
+    }
+}
+
+/// Returns the flattened column names of a value.
+pub fn columns(value: &impl Serialize) -> Vec<String> {
+    let mut map = Map::new();
+    if let Ok(value) = serde_json::to_value(value) {
+        flatten(&mut map, String::new(), value);
+    }
+    map.into_iter().map(|(key, _)| key).collect()
+}
+
+/// Writes entries as CSV with the given columns.
+///
+/// Nested fields are flattened into columns with dotted names.
+/// Fields without a column are omitted.
+fn write_csv<T: Serialize>(
+    writer: impl Write,
+    columns: &[String],
+    entries: impl IntoIterator<Item = T>,
+) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(columns).map_err(csv_error)?;
+    for entry in entries {
+        let mut row = Map::new();
+        flatten(&mut row, String::new(), serde_json::to_value(entry)?);
+        writer
+            .write_record(columns.iter().map(|column| match row.get(column) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(string)) => string.clone(),
+                Some(value) => value.to_string(),
+            }))
+            .map_err(csv_error)?;
+    }
+    writer.flush()
+}
+
+/// Converts a CSV error, keeping the kind of IO errors.
+fn csv_error(err: csv::Error) -> io::Error {
+    if err.is_io_error() {
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => err,
+            _ => unreachable!("checked io error"),
+        }
+    } else {
+        io::Error::other(err)
+    }
+}
+
+/// Flattens a value into a map with dotted keys.
+fn flatten(map: &mut Map<String, Value>, prefix: String, value: Value) {
+    let key = |name: &dyn ToString| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{}", name.to_string())
+        }
+    };
+    match value {
+        Value::Object(object) => {
+            for (name, value) in object {
+                flatten(map, key(&name), value);
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.into_iter().enumerate() {
+                flatten(map, key(&index), value);
+            }
+        }
+        value => {
+            map.insert(
+                if prefix.is_empty() {
+                    "value".into()
+                } else {
+                    prefix
+                },
+                value,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Entry {
+        id: u64,
+        name: Vec<&'static str>,
+        extra: Option<f32>,
+    }
+
+    fn entries() -> [Entry; 2] {
+        [
+            Entry {
+                id: 1,
+                name: vec!["Character", ":Account.1234"],
+                extra: None,
+            },
+            Entry {
+                id: 2,
+                name: vec!["NPC, with comma"],
+                extra: Some(0.5),
+            },
+        ]
+    }
+
+    fn write(format: Format) -> String {
+        let columns = ["id", "name.0", "name.1", "extra"].map(String::from);
+        let mut output = Vec::new();
+        format
+            .write_entries(&mut output, &columns, entries())
+            .expect("failed to write");
+        String::from_utf8(output).expect("invalid utf8")
+    }
+
+    #[test]
+    fn ndjson() {
+        assert_eq!(
+            write(Format::Ndjson),
+            "{\"id\":1,\"name\":[\"Character\",\":Account.1234\"],\"extra\":null}\n\
+             {\"id\":2,\"name\":[\"NPC, with comma\"],\"extra\":0.5}\n"
+        );
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            write(Format::Csv),
+            "id,name.0,name.1,extra\n\
+             1,Character,:Account.1234,\n\
+             2,\"NPC, with comma\",,0.5\n"
+        );
+    }
+}
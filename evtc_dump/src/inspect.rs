@@ -0,0 +1,345 @@
+use crate::{error, stdio};
+use clap::error::ErrorKind;
+use evtc_parse::{
+    AgentInfo, Event, EventKind, Log, ParseError, StateChange, analysis::outcome,
+    legacy::LegacyEventCategory,
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Inspect arguments.
+#[derive(Debug, Clone, clap::Args)]
+pub struct Inspect {
+    /// Input path to EVTC file or `-` for stdin.
+    pub input: PathBuf,
+}
+
+impl Inspect {
+    /// Prints the log summary.
+    pub fn run(&self) {
+        let summary = self
+            .summary()
+            .unwrap_or_else(|err| error(ErrorKind::Io, format!("failed to parse EVTC log: {err}")));
+        if let Err(err) = write!(io::stdout(), "{summary}") {
+            stdio::exit_on_broken_pipe(&err);
+            error(ErrorKind::Io, format!("failed printing to stdout: {err}"));
+        }
+    }
+
+    /// Parses the log and creates its summary.
+    pub fn summary(&self) -> Result<Summary, ParseError> {
+        stdio::parse(&self.input).map(Summary::new)
+    }
+}
+
+/// Human-readable summary of a log.
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    /// Date the log was recorded.
+    pub date: String,
+
+    /// EVTC revision.
+    pub revision: u8,
+
+    /// Species id of the boss, `1` for WvW and `2` for map logs.
+    pub boss_id: u16,
+
+    /// Identified encounter.
+    pub encounter: String,
+
+    /// ArcDPS build string.
+    pub arc_build: Option<String>,
+
+    /// Game build.
+    pub gw2_build: Option<u64>,
+
+    /// Map id.
+    pub map_id: Option<u64>,
+
+    /// Game client language.
+    pub language: Option<String>,
+
+    /// Recording player as character and account name.
+    pub pov: Option<String>,
+
+    /// Encounter duration in milliseconds.
+    pub duration: u64,
+
+    /// Whether the encounter was successful.
+    pub success: bool,
+
+    /// Players sorted by subgroup and character name.
+    pub players: Vec<Player>,
+
+    /// Amount of events per kind, most frequent first.
+    ///
+    /// Combat events are split into strikes, buff damage and legacy activation and buff events.
+    pub event_counts: Vec<(String, usize)>,
+}
+
+/// Player in the log roster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Player {
+    /// Character name.
+    pub character: String,
+
+    /// Account name without leading colon.
+    pub account: String,
+
+    /// Squad subgroup.
+    pub subgroup: Option<u8>,
+
+    /// Profession with elite specialization, if any.
+    pub profession: String,
+}
+
+impl Summary {
+    /// Creates a summary of the log.
+    pub fn new(log: Log) -> Self {
+        let mut counts = BTreeMap::<_, usize>::new();
+        for event in &log.events {
+            *counts.entry(count_key(event)).or_default() += 1;
+        }
+        let mut event_counts = counts
+            .into_iter()
+            .map(|((statechange, category), count)| {
+                let name = match (statechange, category) {
+                    (StateChange::Combat, Some(category)) => format!("Combat ({category:?})"),
+                    (StateChange::Combat, None) => "Combat (Strike)".into(),
+                    (statechange, _) => format!("{statechange:?}"),
+                };
+                (name, count)
+            })
+            .collect::<Vec<_>>();
+        event_counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+        let encounter = log.encounter();
+        let log = log.into_transformed();
+        let outcome = outcome(&log);
+
+        let mut summary = Self {
+            date: log.header.date.clone(),
+            revision: log.header.revision,
+            boss_id: log.header.boss_id,
+            encounter: format!("{encounter:?}"),
+            arc_build: None,
+            gw2_build: None,
+            map_id: None,
+            language: None,
+            pov: None,
+            duration: outcome.duration(),
+            success: outcome.success,
+            players: log
+                .agents
+                .iter()
+                .filter_map(|agent| match agent.info() {
+                    AgentInfo::Player {
+                        character,
+                        account,
+                        subgroup,
+                        profession,
+                        specialization,
+                    } => Some(Player {
+                        character: character.into(),
+                        account: account.into(),
+                        subgroup,
+                        profession: match u32::from(specialization) {
+                            0 => format!("{profession:?}"),
+                            _ => format!("{profession:?} ({specialization:?})"),
+                        },
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            event_counts,
+        };
+
+        for event in &log.events {
+            match event {
+                EventKind::ArcBuild(event) => summary.arc_build = Some(event.build.clone()),
+                EventKind::GWBuild { build, .. } => summary.gw2_build = Some(*build),
+                EventKind::MapId { map, .. } => summary.map_id = Some(*map),
+                EventKind::Language { language, .. } => {
+                    summary.language = Some(match language {
+                        Ok(language) => format!("{language:?}"),
+                        Err(id) => format!("Unknown ({id})"),
+                    })
+                }
+                EventKind::PointOfView(event) => {
+                    summary.pov = log.agent(event.agent.id).map(|agent| {
+                        match (agent.character_name(), agent.account_name()) {
+                            (Some(character), Some(account)) => format!("{character} ({account})"),
+                            _ => agent.name.first().cloned().unwrap_or_default(),
+                        }
+                    })
+                }
+                _ => {}
+            }
+        }
+        summary.players.sort_by(|a, b| {
+            a.subgroup
+                .cmp(&b.subgroup)
+                .then_with(|| a.character.cmp(&b.character))
+        });
+
+        summary
+    }
+}
+
+/// Returns the key an event is counted under.
+///
+/// Combat events are additionally keyed by their category, strikes have none.
+fn count_key(event: &Event) -> (StateChange, Option<LegacyEventCategory>) {
+    match event.get_statechange() {
+        StateChange::Combat => (StateChange::Combat, LegacyEventCategory::from_event(event)),
+        statechange => (statechange, None),
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn optional(value: &Option<impl fmt::Display>) -> String {
+            value
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "-".into())
+        }
+
+        writeln!(f, "Date:       {}", self.date)?;
+        writeln!(f, "Revision:   {}", self.revision)?;
+        writeln!(f, "Boss id:    {} ({})", self.boss_id, self.encounter)?;
+        writeln!(f, "Arc build:  {}", optional(&self.arc_build))?;
+        writeln!(f, "GW2 build:  {}", optional(&self.gw2_build))?;
+        writeln!(f, "Map id:     {}", optional(&self.map_id))?;
+        writeln!(f, "Language:   {}", optional(&self.language))?;
+        writeln!(f, "POV:        {}", optional(&self.pov))?;
+        writeln!(
+            f,
+            "Duration:   {}.{:03}s ({})",
+            self.duration / 1000,
+            self.duration % 1000,
+            if self.success { "success" } else { "failure" }
+        )?;
+
+        writeln!(f, "Players:    {}", self.players.len())?;
+        for player in &self.players {
+            writeln!(
+                f,
+                "  [{}] {} ({}) - {}",
+                optional(&player.subgroup),
+                player.character,
+                player.account,
+                player.profession
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Events:     {}",
+            self.event_counts
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>()
+        )?;
+        for (name, count) in &self.event_counts {
+            writeln!(f, "  {name}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evtc_parse::{
+        Agent,
+        fixture::{self, statechange as event},
+    };
+    use std::fs;
+
+    #[test]
+    fn summary() {
+        let log = fixture::log(
+            15438,
+            vec![
+                Agent {
+                    is_elite: 62,
+                    ..fixture::agent(1, &["Zed", ":Account.1234", "2"])
+                },
+                Agent {
+                    profession: 2,
+                    ..fixture::agent(2, &["Alpha", ":Other.5678", "1"])
+                },
+                fixture::npc(3, "Vale Guardian", 15438),
+            ],
+            vec![
+                event(0, 2, StateChange::PointOfView),
+                event(0, 38, StateChange::MapId),
+                event(1000, 1, StateChange::EnterCombat),
+                event(1500, 2, StateChange::EnterCombat),
+                event(2000, 1, StateChange::Combat),
+                Event {
+                    buff: 1,
+                    buff_dmg: 100,
+                    ..event(2500, 1, StateChange::Combat)
+                },
+                Event {
+                    is_activation: 1,
+                    ..event(3000, 2, StateChange::Combat)
+                },
+                event(5000, 3, StateChange::ChangeDead),
+            ],
+        );
+
+        let summary = Summary::new(log);
+        assert_eq!(summary.encounter, "ValeGuardian");
+        assert_eq!(summary.map_id, Some(38));
+        assert_eq!(summary.pov.as_deref(), Some("Alpha (Other.5678)"));
+        assert_eq!(
+            summary.players,
+            [
+                Player {
+                    character: "Alpha".into(),
+                    account: "Other.5678".into(),
+                    subgroup: Some(1),
+                    profession: "Warrior".into(),
+                },
+                Player {
+                    character: "Zed".into(),
+                    account: "Account.1234".into(),
+                    subgroup: Some(2),
+                    profession: "Guardian (Firebrand)".into(),
+                },
+            ]
+        );
+        assert_eq!(summary.event_counts[0], ("EnterCombat".into(), 2));
+        assert!(
+            [
+                "Combat (Strike)",
+                "Combat (BuffDamage)",
+                "Combat (Activation)"
+            ]
+            .iter()
+            .all(|name| summary.event_counts.contains(&(name.to_string(), 1)))
+        );
+        assert!(summary.to_string().contains("Players:    2\n"));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let path = std::env::temp_dir().join(format!("evtc_dump_{}.evtc", std::process::id()));
+        fs::write(&path, b"junk").expect("failed to write file");
+        let result = Inspect {
+            input: path.clone(),
+        }
+        .summary();
+        fs::remove_file(&path).expect("failed to remove file");
+
+        assert!(matches!(result, Err(ParseError::NotEvtc)));
+    }
+}
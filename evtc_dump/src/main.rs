@@ -1,84 +1,66 @@
-use clap::{Parser, ValueEnum};
-use evtc_parse::parse_file;
-use serde::Serialize;
-use std::{
-    fs::File,
-    io::BufWriter,
-    path::{Path, PathBuf},
-};
-use strum::Display;
+mod batch;
+mod dump;
+mod filter;
+mod format;
+mod inspect;
+mod stdio;
+
+use self::{batch::Batch, dump::Dump, inspect::Inspect};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, error::ErrorKind};
+use std::fmt;
 
 /// CLI arguments.
+///
+/// Dumps log data if no subcommand is given.
 #[derive(Debug, Clone, Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    /// Input path to EVTC file.
-    pub input: PathBuf,
-
-    /// Output path to JSON file.
-    ///
-    /// Defaults to input path with JSON file extension.
-    pub output: Option<PathBuf>,
+    /// Subcommand.
+    #[clap(subcommand)]
+    pub command: Option<Command>,
 
-    /// Data to dump.
-    #[clap(value_enum, long, short, default_value_t)]
-    pub data: Data,
+    /// Dump arguments without subcommand.
+    #[clap(flatten)]
+    pub dump: Dump,
 }
 
 impl Args {
-    /// Returns the path to the output file.
-    fn output_path(&self) -> PathBuf {
-        self.output
-            .as_ref()
-            .cloned()
-            .unwrap_or_else(|| Path::new(&self.input).with_extension("json"))
-    }
-
-    /// Saves data to the output file.
-    fn save(&self, data: &impl Serialize) {
-        let path = self.output_path();
-        let file = File::create(&path).expect("failed to create output file");
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &data).expect("failed to write events");
-        println!("Dumped {} data to \"{}\"", self.data, path.display());
+    /// Parses the [`Command`] from the CLI arguments.
+    ///
+    /// Dump arguments are only parsed without a subcommand, as their input is required.
+    fn parse_command() -> Command {
+        let mut matches = Self::command().get_matches();
+        let command = if matches.subcommand().is_some() {
+            Command::from_arg_matches_mut(&mut matches)
+        } else {
+            Dump::from_arg_matches_mut(&mut matches).map(Command::Dump)
+        };
+        command.unwrap_or_else(|err| err.format(&mut Self::command()).exit())
     }
 }
 
-/// Data to dump.
-#[derive(Debug, Display, Default, Clone, ValueEnum)]
-#[strum(serialize_all = "lowercase")]
-enum Data {
-    /// All log data.
-    #[default]
-    All,
+/// Subcommands.
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Dump log data.
+    Dump(Dump),
 
-    /// Log agents.
-    Agents,
+    /// Print a summary of the log.
+    Inspect(Inspect),
 
-    /// Skill & buff information.
-    Skills,
+    /// Process all logs in a directory or matching a glob pattern.
+    Batch(Batch),
+}
 
-    /// Log events.
-    Events,
+/// Exits with a CLI error.
+fn error(kind: ErrorKind, message: impl fmt::Display) -> ! {
+    Args::command().error(kind, message).exit()
 }
 
 fn main() {
-    let args = Args::parse();
-
-    println!("Parsing \"{}\"...", args.input.display());
-
-    let log = parse_file(&args.input)
-        .expect("failed to parse EVTC log")
-        .into_transformed();
-
-    println!(
-        "Parsed {} log for encounter id {}",
-        log.header.date, log.header.boss_id
-    );
-
-    match args.data {
-        Data::All => args.save(&log),
-        Data::Agents => args.save(&log.agents),
-        Data::Skills => args.save(&log.skills),
-        Data::Events => args.save(&log.events),
+    match Args::parse_command() {
+        Command::Dump(dump) => dump.run(),
+        Command::Inspect(inspect) => inspect.run(),
+        Command::Batch(batch) => batch.run(),
     }
 }
@@ -0,0 +1,147 @@
+use evtc_parse::{Log, LogReader, ParseError, ParseOptions, ZipArchive};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read, Write},
+    path::Path,
+    process,
+};
+
+/// Path referring to stdin or stdout.
+pub const STDIO: &str = "-";
+
+/// Magic bytes of a compressed zip archive.
+const ZIP_MAGIC: &[u8] = b"PK";
+
+/// Magic bytes of an uncompressed EVTC log.
+const EVTC_MAGIC: &[u8] = b"EVTC";
+
+/// Whether the path refers to stdin or stdout.
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Returns a displayable name for the path, using the stream name for stdin or stdout.
+pub fn name(path: &Path, stream: &str) -> String {
+    if is_stdio(path) {
+        stream.into()
+    } else {
+        format!("\"{}\"", path.display())
+    }
+}
+
+/// Input of an uncompressed or compressed log.
+pub enum Input {
+    /// Uncompressed log read as stream.
+    Evtc(Box<dyn BufRead>),
+
+    /// Compressed log archive.
+    Zevtc(ZipArchive<Cursor<Vec<u8>>>),
+}
+
+impl Input {
+    /// Opens the file at the path or stdin.
+    ///
+    /// Compressed logs are detected by their magic bytes instead of the file extension.
+    /// Compressed logs are read into memory, as archives require seeking.
+    pub fn open(path: &Path) -> Result<Self, ParseError> {
+        let input: Box<dyn BufRead> = if is_stdio(path) {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+        Self::from_reader(input)
+    }
+
+    /// Creates an input from a reader of an uncompressed or compressed log.
+    ///
+    /// Returns [`ParseError::NotEvtc`] if the input starts with neither magic bytes.
+    pub fn from_reader(mut input: Box<dyn BufRead>) -> Result<Self, ParseError> {
+        // a single read may return less bytes, for example from a pipe
+        let mut magic = Vec::with_capacity(EVTC_MAGIC.len());
+        input
+            .by_ref()
+            .take(EVTC_MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+
+        if magic.starts_with(ZIP_MAGIC) {
+            let mut data = magic;
+            input.read_to_end(&mut data)?;
+            Ok(Self::Zevtc(ZipArchive::new(Cursor::new(data))?))
+        } else if magic.starts_with(EVTC_MAGIC) {
+            Ok(Self::Evtc(Box::new(Cursor::new(magic).chain(input))))
+        } else {
+            Err(ParseError::NotEvtc)
+        }
+    }
+
+    /// Parses the full log.
+    pub fn parse(self) -> Result<Log, ParseError> {
+        match self {
+            Self::Evtc(input) => Log::parse_with(input, ParseOptions::default()),
+            Self::Zevtc(mut archive) => LogReader::from_zevtc(&mut archive)?.into_log(),
+        }
+    }
+}
+
+/// Parses a log from the file at the path or stdin.
+///
+/// Compressed logs are detected by their magic bytes instead of the file extension.
+pub fn parse(path: &Path) -> Result<Log, ParseError> {
+    Input::open(path)?.parse()
+}
+
+/// Exits successfully if the error is caused by a closed stdout pipe, e.g. when piping into `head`.
+pub fn exit_on_broken_pipe(err: &io::Error) {
+    if err.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+}
+
+/// Prints a status message to stdout, or to stderr if stdout is used for data.
+pub fn status(message: impl fmt::Display, data_to_stdout: bool) {
+    if data_to_stdout {
+        eprintln!("{message}");
+    } else if let Err(err) = writeln!(io::stdout(), "{message}") {
+        exit_on_broken_pipe(&err);
+        panic!("failed printing to stdout: {err}");
+    }
+}
+
+/// Creates a writer to the file at the path or stdout.
+pub fn create(path: &Path) -> io::Result<Box<dyn Write>> {
+    if is_stdio(path) {
+        Ok(Box::new(io::stdout().lock()))
+    } else {
+        Ok(Box::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evtc_parse::{Save, fixture};
+
+    #[test]
+    fn magic() {
+        // buffer single bytes like a slow pipe
+        let parse = |data: Vec<u8>| {
+            Input::from_reader(Box::new(BufReader::with_capacity(1, Cursor::new(data))))?.parse()
+        };
+        let log = fixture::log(15438, Vec::new(), Vec::new());
+
+        let mut evtc = Vec::new();
+        log.save(&mut evtc).expect("failed to save log");
+        let parsed = parse(evtc).expect("failed to parse log");
+        assert_eq!(parsed.header.boss_id, 15438);
+
+        let mut zevtc = Cursor::new(Vec::new());
+        log.save_zevtc(&mut zevtc).expect("failed to save log");
+        let parsed = parse(zevtc.into_inner()).expect("failed to parse log");
+        assert_eq!(parsed.header.boss_id, 15438);
+
+        assert!(matches!(parse(b"junk".to_vec()), Err(ParseError::NotEvtc)));
+        assert!(matches!(parse(b"EV".to_vec()), Err(ParseError::NotEvtc)));
+        assert!(matches!(parse(Vec::new()), Err(ParseError::NotEvtc)));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
-    Parse, ParseError, Save,
-    util::{Endian, read_string_buffer, write_string_buffer},
+    Parse, ParseError, Save, StringDecoding,
+    util::{Endian, decode_string, read_buffer, write_string_buffer},
 };
 use byteorder::{ReadBytesExt, WriteBytesExt};
-use evtc::AgentKind;
+use evtc::{AgentKind, Profession, Specialization, agent::strip_account_prefix};
 use std::io;
 
 #[cfg(feature = "serde")]
@@ -65,27 +65,61 @@ impl Agent {
         AgentKind::new(self.profession, self.is_elite)
     }
 
-    /// Parses name information from the input.
-    fn parse_name(input: &mut impl io::Read) -> Result<Vec<String>, ParseError> {
-        let string = read_string_buffer::<{ Self::NAME_SIZE }>(input)?;
-        Ok(string
-            .split('\0')
-            .filter(|part| !part.is_empty())
-            .map(|part| part.to_string())
-            .collect())
+    /// Checks whether the agent is a player.
+    #[inline]
+    pub const fn is_player(&self) -> bool {
+        matches!(self.kind(), AgentKind::Player)
     }
 
-    /// Saves name information to the output.
-    fn save_name(&self, output: &mut impl io::Write) -> Result<(), io::Error> {
-        let string = self.name.join("\0");
-        write_string_buffer::<{ Self::NAME_SIZE }>(output, &string)
+    /// Returns the character name for player agents.
+    #[inline]
+    pub fn character_name(&self) -> Option<&str> {
+        self.player_name_part(0)
     }
-}
 
-impl Parse for Agent {
-    type Error = ParseError;
+    /// Returns the account name without `':'` prefix for player agents.
+    #[inline]
+    pub fn account_name(&self) -> Option<&str> {
+        self.player_name_part(1).map(strip_account_prefix)
+    }
 
-    fn parse(input: &mut impl io::Read) -> Result<Self, Self::Error> {
+    /// Returns the subgroup for player agents.
+    #[inline]
+    pub fn subgroup(&self) -> Option<u8> {
+        self.player_name_part(2)?.parse().ok()
+    }
+
+    /// Returns a part of the name combo string for player agents.
+    #[inline]
+    fn player_name_part(&self, index: usize) -> Option<&str> {
+        if self.is_player() {
+            self.name.get(index).map(String::as_str)
+        } else {
+            None
+        }
+    }
+
+    /// Returns structured information about the agent.
+    pub fn info(&self) -> AgentInfo<'_> {
+        let name = self.name.first().map(String::as_str).unwrap_or_default();
+        match self.kind() {
+            AgentKind::Player => AgentInfo::Player {
+                character: name,
+                account: self.account_name().unwrap_or_default(),
+                subgroup: self.subgroup(),
+                profession: self.profession.into(),
+                specialization: self.is_elite.into(),
+            },
+            AgentKind::Npc(species) => AgentInfo::Npc { species, name },
+            AgentKind::Gadget(id) => AgentInfo::Gadget { id, name },
+        }
+    }
+
+    /// Parses an agent from the input with the given [`StringDecoding`] for the name.
+    pub fn parse_with(
+        input: &mut impl io::Read,
+        decoding: StringDecoding,
+    ) -> Result<Self, ParseError> {
         let id = input.read_u64::<Endian>()?;
         let profession = input.read_u32::<Endian>()?;
         let is_elite = input.read_u32::<Endian>()?;
@@ -96,7 +130,7 @@ impl Parse for Agent {
         let condition = input.read_i16::<Endian>()?;
         let hitbox_height = input.read_u16::<Endian>()?;
 
-        let name = Self::parse_name(input)?;
+        let name = Self::parse_name(input, decoding)?;
 
         // padding added by c
         input.read_u32::<Endian>()?;
@@ -114,6 +148,35 @@ impl Parse for Agent {
             condition,
         })
     }
+
+    /// Parses name information from the input.
+    ///
+    /// Each part of the combo string is decoded separately.
+    fn parse_name(
+        input: &mut impl io::Read,
+        decoding: StringDecoding,
+    ) -> Result<Vec<String>, ParseError> {
+        let buffer = read_buffer::<{ Self::NAME_SIZE }>(input)?;
+        buffer
+            .split(|byte| *byte == 0)
+            .filter(|part| !part.is_empty())
+            .map(|part| decode_string(part, decoding))
+            .collect()
+    }
+
+    /// Saves name information to the output.
+    fn save_name(&self, output: &mut impl io::Write) -> Result<(), io::Error> {
+        let string = self.name.join("\0");
+        write_string_buffer::<{ Self::NAME_SIZE }>(output, &string)
+    }
+}
+
+impl Parse for Agent {
+    type Error = ParseError;
+
+    fn parse(input: &mut impl io::Read) -> Result<Self, Self::Error> {
+        Self::parse_with(input, StringDecoding::Strict)
+    }
 }
 
 impl Save for Agent {
@@ -137,6 +200,47 @@ impl Save for Agent {
     }
 }
 
+/// Structured information about an [`Agent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AgentInfo<'a> {
+    /// Agent is a player.
+    Player {
+        /// Character name.
+        character: &'a str,
+
+        /// Account name without `':'` prefix.
+        account: &'a str,
+
+        /// Subgroup, if present.
+        subgroup: Option<u8>,
+
+        /// Profession of the player.
+        profession: Profession,
+
+        /// Elite specialization of the player.
+        specialization: Specialization,
+    },
+
+    /// Agent is an NPC.
+    Npc {
+        /// Species id of the NPC.
+        species: u16,
+
+        /// Name of the NPC.
+        name: &'a str,
+    },
+
+    /// Agent is a gadget.
+    Gadget {
+        /// Volatile pseudo id of the gadget.
+        id: u16,
+
+        /// Name of the gadget.
+        name: &'a str,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,7 +250,7 @@ mod tests {
         let name: Vec<String> = vec!["Character".into(), ":Account.1234".into(), "1".into()];
         let data: &[u8; Agent::NAME_SIZE] = b"Character\0:Account.1234\x001\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 
-        let parsed = Agent::parse_name(io::Cursor::new(data.as_slice()).get_mut())
+        let parsed = Agent::parse_name(&mut data.as_slice(), StringDecoding::Strict)
             .expect("failed to parse agent name");
         assert_eq!(name, parsed, "incorrect agent name");
 
@@ -169,4 +273,66 @@ mod tests {
             .expect("failed to save agent");
         assert_eq!(data, &buffer, "incorrect saved data");
     }
+
+    #[test]
+    fn player_info() {
+        let agent = Agent {
+            id: 1,
+            name: vec!["Character".into(), ":Account.1234".into(), "3".into()],
+            profession: Profession::Guardian.into(),
+            is_elite: Specialization::Firebrand.into(),
+            hitbox_width: 0,
+            hitbox_height: 0,
+            toughness: 0,
+            concentration: 0,
+            healing: 0,
+            condition: 0,
+        };
+        assert_eq!(agent.character_name(), Some("Character"));
+        assert_eq!(agent.account_name(), Some("Account.1234"));
+        assert_eq!(agent.subgroup(), Some(3));
+        assert_eq!(
+            agent.info(),
+            AgentInfo::Player {
+                character: "Character",
+                account: "Account.1234",
+                subgroup: Some(3),
+                profession: Profession::Guardian,
+                specialization: Specialization::Firebrand,
+            }
+        );
+
+        let npc = Agent {
+            name: vec!["Vale Guardian".into()],
+            profession: 15438,
+            is_elite: u32::MAX,
+            ..agent
+        };
+        assert_eq!(npc.character_name(), None);
+        assert_eq!(npc.account_name(), None);
+        assert_eq!(
+            npc.info(),
+            AgentInfo::Npc {
+                species: 15438,
+                name: "Vale Guardian",
+            }
+        );
+    }
+
+    #[test]
+    fn agent_name_invalid() {
+        let mut data = [0; Agent::NAME_SIZE];
+        data[..13].copy_from_slice(b"Char\xc3\0:Acc\0\x31\0");
+
+        let result = Agent::parse_name(&mut data.as_slice(), StringDecoding::Strict);
+        assert!(matches!(result, Err(ParseError::FromUtf8Error(_))));
+
+        let parsed = Agent::parse_name(&mut data.as_slice(), StringDecoding::Truncate)
+            .expect("failed to parse agent name");
+        assert_eq!(parsed, ["Char", ":Acc", "1"]);
+
+        let parsed = Agent::parse_name(&mut data.as_slice(), StringDecoding::Lossy)
+            .expect("failed to parse agent name");
+        assert_eq!(parsed, ["Char\u{fffd}", ":Acc", "1"]);
+    }
 }
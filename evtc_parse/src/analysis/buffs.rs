@@ -0,0 +1,558 @@
+use crate::{
+    EventKind, LogTransformed,
+    buff::{BuffInfo, BuffStackType},
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Uptime statistics of a buff on an agent.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuffUptime {
+    /// Time the buff was present in milliseconds.
+    pub uptime: u64,
+
+    /// Sum of stacks present over time in stack milliseconds.
+    pub stack_time: u64,
+
+    /// Time of stacks present per source agent id in milliseconds.
+    pub generation: HashMap<u64, u64>,
+}
+
+impl BuffUptime {
+    /// Returns the ratio of the given duration the buff was present.
+    #[inline]
+    pub fn uptime_ratio(&self, duration: u64) -> f64 {
+        ratio(self.uptime, duration)
+    }
+
+    /// Returns the average amount of stacks over the given duration.
+    #[inline]
+    pub fn average_stacks(&self, duration: u64) -> f64 {
+        ratio(self.stack_time, duration)
+    }
+
+    /// Returns the average amount of stacks generated by the source over the given duration.
+    #[inline]
+    pub fn generation_ratio(&self, source: u64, duration: u64) -> f64 {
+        ratio(
+            self.generation.get(&source).copied().unwrap_or_default(),
+            duration,
+        )
+    }
+}
+
+/// Computes a ratio, returning `0` for an empty duration.
+#[inline]
+fn ratio(value: u64, duration: u64) -> f64 {
+    if duration > 0 {
+        value as f64 / duration as f64
+    } else {
+        0.0
+    }
+}
+
+/// Buff uptimes of a log.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuffUptimes {
+    /// Time of the first simulated event.
+    pub start: u64,
+
+    /// Time the simulation ended.
+    pub end: u64,
+
+    /// Uptime per pair of agent id and buff id.
+    pub buffs: HashMap<(u64, u32), BuffUptime>,
+}
+
+impl BuffUptimes {
+    /// Returns the simulated duration in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns the [`BuffUptime`] of the buff on the agent.
+    #[inline]
+    pub fn get(&self, agent: u64, buff: u32) -> Option<&BuffUptime> {
+        self.buffs.get(&(agent, buff))
+    }
+}
+
+/// Simulates buff uptimes from [`EventKind::BuffApply`], [`EventKind::BuffInitial`], [`EventKind::BuffChange`],
+/// [`EventKind::BuffRemoveSingle`], [`EventKind::BuffRemoveAll`], [`EventKind::StackActive`] and [`EventKind::StackReset`] events.
+///
+/// Stacking behavior is taken from [`EventKind::BuffInfo`] events.
+/// Buffs stacking in intensity tick all stacks simultaneously up to their maximum stacks.
+/// Buffs stacking in duration only tick the active stack, capped at the maximum duration.
+/// Buffs without [`BuffInfo`] are treated as stacking in intensity.
+///
+/// Buff states are only advanced when touched by an event, using the time elapsed since their last update.
+#[derive(Debug, Default, Clone)]
+pub struct BuffSimulator {
+    infos: HashMap<u32, BuffInfo>,
+    states: HashMap<(u64, u32), BuffState>,
+
+    /// Buff id per pair of agent id and stack id.
+    ///
+    /// Entries of expired stacks are kept and verified on lookup.
+    stacks: HashMap<(u64, u32), u32>,
+
+    start: Option<u64>,
+    time: u64,
+}
+
+impl BuffSimulator {
+    /// Creates a new simulator.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds stacking information for a buff.
+    #[inline]
+    pub fn add_info(&mut self, info: BuffInfo) {
+        self.infos.insert(info.skill_id, info);
+    }
+
+    /// Processes the next event.
+    pub fn process(&mut self, event: &EventKind) {
+        match event {
+            EventKind::BuffInfo(info) => self.add_info(info.clone()),
+            EventKind::BuffApply(event) => {
+                let common = &event.common;
+                self.apply(
+                    common.time,
+                    (common.target.id, common.skill_id),
+                    Stack {
+                        id: event.stack_id,
+                        source: common.source.id,
+                        remaining: event.duration.max(0) as u64,
+                    },
+                    event.stack_active,
+                );
+            }
+            EventKind::BuffInitial(event) => {
+                let common = &event.common;
+                self.apply(
+                    common.time,
+                    (common.target.id, common.skill_id),
+                    Stack {
+                        id: event.stack_id,
+                        source: common.source.id,
+                        remaining: event.duration.max(0) as u64,
+                    },
+                    event.stack_active,
+                );
+            }
+            EventKind::BuffChange(event) => {
+                let common = &event.common;
+                if let Some(stack) = self
+                    .state_at(common.time, (common.target.id, common.skill_id))
+                    .stack_mut(event.stack_id)
+                {
+                    stack.remaining = event.new_duration as u64;
+                }
+            }
+            EventKind::BuffRemoveSingle(event) => {
+                // source is the agent losing the buff
+                let common = &event.common;
+                self.stacks.remove(&(common.source.id, event.stack_id));
+                self.state_at(common.time, (common.source.id, common.skill_id))
+                    .stacks
+                    .retain(|stack| stack.id != event.stack_id);
+            }
+            EventKind::BuffRemoveAll(event) => {
+                let common = &event.common;
+                self.state_at(common.time, (common.source.id, common.skill_id))
+                    .stacks
+                    .clear();
+            }
+            EventKind::StackActive(event) => {
+                if let Some(state) =
+                    self.state_by_stack(event.time, event.agent.id, event.stack_id as u32)
+                {
+                    state.active = Some(event.stack_id as u32);
+                }
+            }
+            EventKind::StackReset(event) => {
+                if let Some(stack) = self
+                    .state_by_stack(event.time, event.agent.id, event.stack_id)
+                    .and_then(|state| state.stack_mut(event.stack_id))
+                {
+                    stack.remaining = event.duration.max(0) as u64;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the time of the last processed event.
+    #[inline]
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Returns the active buffs of the agent with their amount of stacks at the time of the last processed event.
+    ///
+    /// Buffs stacking in duration count as a single stack.
+    pub fn active_buffs(&self, agent: u64) -> HashMap<u32, u32> {
+        self.states
+            .iter()
+            .filter(|((id, _), _)| *id == agent)
+            .filter_map(|((_, buff), state)| {
+                let (intensity, _, _) = self.stacking(*buff);
+                let elapsed = self.time.saturating_sub(state.time);
+                let stacks = state.remaining_stacks(elapsed, intensity);
+                (stacks > 0).then_some((*buff, stacks as u32))
+            })
+            .collect()
+    }
+
+    /// Finishes the simulation at the given time.
+    pub fn finish(mut self, end: u64) -> BuffUptimes {
+        self.advance(end);
+        let end = self.time;
+        let keys = self.states.keys().copied().collect::<Vec<_>>();
+        for key in keys {
+            self.advance_state(end, key);
+        }
+        BuffUptimes {
+            start: self.start.unwrap_or(end),
+            end,
+            buffs: self
+                .states
+                .into_iter()
+                .map(|(key, state)| (key, state.uptime))
+                .collect(),
+        }
+    }
+
+    /// Applies a new stack.
+    fn apply(&mut self, time: u64, key: (u64, u32), stack: Stack, active: bool) {
+        self.stacks.insert((key.0, stack.id), key.1);
+        let (intensity, max_stacks, duration_cap) = self.stacking(key.1);
+        let state = self.state_at(time, key);
+        if active {
+            state.active = Some(stack.id);
+        }
+        state.stacks.push(stack);
+
+        // replace stacks with the least remaining duration
+        while state.stacks.len() > max_stacks {
+            if let Some(index) = state
+                .stacks
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, stack)| stack.remaining)
+                .map(|(index, _)| index)
+            {
+                state.stacks.remove(index);
+            }
+        }
+
+        if !intensity && duration_cap > 0 {
+            let total = state
+                .stacks
+                .iter()
+                .map(|stack| stack.remaining)
+                .sum::<u64>();
+            let excess = total.saturating_sub(duration_cap);
+            if let Some(last) = state.stacks.last_mut() {
+                last.remaining = last.remaining.saturating_sub(excess);
+            }
+        }
+    }
+
+    /// Returns whether the buff stacks in intensity, the maximum stacks and the duration cap.
+    fn stacking(&self, buff: u32) -> (bool, usize, u64) {
+        match self.infos.get(&buff) {
+            Some(info) => {
+                let intensity = matches!(
+                    BuffStackType::try_from(info.stacking_type),
+                    Ok(BuffStackType::Stacking | BuffStackType::StackingConditionalLoss) | Err(_)
+                );
+                let max_stacks = if info.max_stacks > 0 {
+                    info.max_stacks as usize
+                } else {
+                    usize::MAX
+                };
+                (intensity, max_stacks, info.duration_cap as u64)
+            }
+            None => (true, usize::MAX, 0),
+        }
+    }
+
+    /// Advances the simulation time to the given time.
+    fn advance(&mut self, time: u64) {
+        self.start.get_or_insert(time);
+        self.time = self.time.max(time);
+    }
+
+    /// Advances the state for the buff on the agent to the given time.
+    fn advance_state(&mut self, time: u64, key: (u64, u32)) -> &mut BuffState {
+        let (intensity, _, _) = self.stacking(key.1);
+        let state = self.states.entry(key).or_default();
+        let elapsed = time.saturating_sub(state.time);
+        if elapsed > 0 {
+            if intensity {
+                state.advance_intensity(elapsed);
+            } else {
+                state.advance_duration(elapsed);
+            }
+        }
+        state.time = state.time.max(time);
+        state
+    }
+
+    /// Returns the state for the buff on the agent advanced to the given time.
+    fn state_at(&mut self, time: u64, key: (u64, u32)) -> &mut BuffState {
+        self.advance(time);
+        let time = self.time;
+        self.advance_state(time, key)
+    }
+
+    /// Returns the state containing the stack of the agent advanced to the given time.
+    fn state_by_stack(&mut self, time: u64, agent: u64, stack_id: u32) -> Option<&mut BuffState> {
+        let buff = *self.stacks.get(&(agent, stack_id))?;
+        Some(self.state_at(time, (agent, buff))).filter(|state| state.has_stack(stack_id))
+    }
+}
+
+/// Simulates [`BuffUptimes`] for the log.
+///
+/// The simulation ends at the time of the last event.
+pub fn buff_uptimes(log: &LogTransformed) -> BuffUptimes {
+    let mut simulator = BuffSimulator::new();
+    let mut end = 0;
+    for event in &log.events {
+        simulator.process(event);
+        if let EventKind::Combat(event) = event {
+            end = end.max(event.common.time);
+        }
+    }
+    let end = end.max(simulator.time);
+    simulator.finish(end)
+}
+
+/// State of a buff on an agent.
+#[derive(Debug, Default, Clone)]
+struct BuffState {
+    /// Time the state was last advanced to.
+    time: u64,
+
+    /// Present stacks.
+    stacks: Vec<Stack>,
+
+    /// Active stack id for buffs stacking in duration.
+    active: Option<u32>,
+
+    /// Accumulated uptime.
+    uptime: BuffUptime,
+}
+
+impl BuffState {
+    /// Returns the stack with the given id.
+    fn stack_mut(&mut self, id: u32) -> Option<&mut Stack> {
+        self.stacks.iter_mut().find(|stack| stack.id == id)
+    }
+
+    /// Checks whether the stack with the given id is present.
+    fn has_stack(&self, id: u32) -> bool {
+        self.stacks.iter().any(|stack| stack.id == id)
+    }
+
+    /// Returns the amount of stacks remaining after the elapsed time without advancing.
+    ///
+    /// Buffs stacking in duration count as a single stack.
+    fn remaining_stacks(&self, elapsed: u64, intensity: bool) -> usize {
+        if intensity {
+            self.stacks
+                .iter()
+                .filter(|stack| stack.remaining > elapsed)
+                .count()
+        } else {
+            let total = self.stacks.iter().map(|stack| stack.remaining).sum::<u64>();
+            (total > elapsed) as usize
+        }
+    }
+
+    /// Advances stacking in intensity, all stacks tick simultaneously.
+    fn advance_intensity(&mut self, elapsed: u64) {
+        let mut uptime = 0;
+        for stack in &mut self.stacks {
+            let ticked = stack.remaining.min(elapsed);
+            uptime = uptime.max(ticked);
+            self.uptime.stack_time += ticked;
+            *self.uptime.generation.entry(stack.source).or_default() += ticked;
+            stack.remaining -= ticked;
+        }
+        self.uptime.uptime += uptime;
+        self.stacks.retain(|stack| stack.remaining > 0);
+    }
+
+    /// Advances stacking in duration, only the active stack ticks.
+    fn advance_duration(&mut self, mut elapsed: u64) {
+        while elapsed > 0 && !self.stacks.is_empty() {
+            let index = self
+                .active
+                .and_then(|id| self.stacks.iter().position(|stack| stack.id == id))
+                .unwrap_or(0);
+            let stack = &mut self.stacks[index];
+            let ticked = stack.remaining.min(elapsed);
+            self.uptime.uptime += ticked;
+            self.uptime.stack_time += ticked;
+            *self.uptime.generation.entry(stack.source).or_default() += ticked;
+            stack.remaining -= ticked;
+            elapsed -= ticked;
+
+            if stack.remaining == 0 {
+                self.stacks.remove(index);
+                self.active = None;
+            }
+        }
+    }
+}
+
+/// A buff stack.
+#[derive(Debug, Clone)]
+struct Stack {
+    /// Stack (instance) id.
+    id: u32,
+
+    /// Source agent id.
+    source: u64,
+
+    /// Remaining duration.
+    remaining: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, StateChange, fixture};
+
+    fn buff_info(buff: u32, stacking: BuffStackType, max_stacks: u16) -> EventKind {
+        Event {
+            skill_id: buff,
+            pad61: stacking.into(),
+            src_master_instance_id: max_stacks,
+            ..fixture::statechange(0, 0, StateChange::BuffInfo)
+        }
+        .into()
+    }
+
+    fn apply(time: u64, source: u64, buff: u32, duration: i32, stack_id: u32) -> EventKind {
+        let [pad61, pad62, pad63, pad64] = stack_id.to_le_bytes();
+        Event {
+            dst_agent: 1,
+            skill_id: buff,
+            value: duration,
+            pad61,
+            pad62,
+            pad63,
+            pad64,
+            ..fixture::statechange(time, source, StateChange::BuffApply)
+        }
+        .into()
+    }
+
+    #[test]
+    fn intensity() {
+        let mut simulator = BuffSimulator::new();
+        for event in [
+            buff_info(740, BuffStackType::Stacking, 2),
+            apply(0, 2, 740, 1000, 1),
+            apply(0, 3, 740, 2000, 2),
+            apply(500, 3, 740, 3000, 3),
+        ] {
+            simulator.process(&event);
+        }
+        let uptimes = simulator.finish(4000);
+        let uptime = uptimes.get(1, 740).expect("missing buff");
+
+        // stack 1 is replaced at 500 after 500ms
+        assert_eq!(uptime.uptime, 3500);
+        assert_eq!(uptime.stack_time, 500 + 2000 + 3000);
+        assert_eq!(uptime.generation[&2], 500);
+        assert_eq!(uptime.generation[&3], 5000);
+        assert_eq!(uptime.average_stacks(uptimes.duration()), 5500.0 / 4000.0);
+    }
+
+    #[test]
+    fn duration() {
+        let mut simulator = BuffSimulator::new();
+        for event in [
+            buff_info(717, BuffStackType::Queue, 5),
+            apply(0, 2, 717, 1000, 1),
+            apply(0, 3, 717, 1000, 2),
+            Event {
+                skill_id: 717,
+                ..fixture::statechange(500, 1, StateChange::BuffRemoveAll)
+            }
+            .into(),
+            apply(3000, 2, 717, 1000, 3),
+        ] {
+            simulator.process(&event);
+        }
+        let uptimes = simulator.finish(5000);
+        let uptime = uptimes.get(1, 717).expect("missing buff");
+
+        assert_eq!(uptime.uptime, 1500);
+        assert_eq!(uptime.stack_time, 1500);
+        assert_eq!(uptime.generation[&2], 1500);
+        assert_eq!(uptime.generation.get(&3), None);
+        assert_eq!(uptime.uptime_ratio(uptimes.duration()), 0.3);
+    }
+
+    #[test]
+    fn duration_max_stacks() {
+        let mut simulator = BuffSimulator::new();
+        for event in [
+            buff_info(719, BuffStackType::Queue, 2),
+            apply(0, 2, 719, 1000, 1),
+            apply(0, 3, 719, 3000, 2),
+            apply(0, 4, 719, 2000, 3),
+        ] {
+            simulator.process(&event);
+        }
+        let uptimes = simulator.finish(10000);
+        let uptime = uptimes.get(1, 719).expect("missing buff");
+
+        assert_eq!(uptime.uptime, 5000);
+        assert_eq!(uptime.generation.get(&2), None);
+        assert_eq!(uptime.generation[&3], 3000);
+        assert_eq!(uptime.generation[&4], 2000);
+    }
+
+    #[test]
+    fn stack_active() {
+        let mut simulator = BuffSimulator::new();
+        for event in [
+            buff_info(717, BuffStackType::Queue, 5),
+            apply(0, 2, 717, 1000, 1),
+            apply(0, 3, 717, 1000, 2),
+            apply(0, 2, 740, 100, 3),
+            Event {
+                dst_agent: 2,
+                ..fixture::statechange(200, 1, StateChange::StackActive)
+            }
+            .into(),
+        ] {
+            simulator.process(&event);
+        }
+
+        // untouched stacks expire without being advanced
+        assert_eq!(simulator.active_buffs(1), HashMap::from([(717, 1)]));
+
+        let uptimes = simulator.finish(3000);
+        let uptime = uptimes.get(1, 717).expect("missing buff");
+        assert_eq!(uptime.uptime, 2000);
+        assert_eq!(uptime.generation[&2], 1000);
+        assert_eq!(uptime.generation[&3], 1000);
+        assert_eq!(uptimes.get(1, 740).expect("missing buff").uptime, 100);
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    EventKind, LogTransformed, LogTransformedLegacy,
+    animation::{AnimationKind, AnimationProgress},
+    legacy::{Activation, LegacyEventKind},
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a skill cast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CastOutcome {
+    /// Stopped after reaching the tooltip time or first trigger point.
+    Fire,
+
+    /// Stopped before reaching the tooltip time or first trigger point.
+    Cancel,
+
+    /// Animation completed fully.
+    Reset,
+
+    /// No matching stop was found.
+    Unknown,
+}
+
+impl From<AnimationProgress> for CastOutcome {
+    #[inline]
+    fn from(progress: AnimationProgress) -> Self {
+        match progress {
+            AnimationProgress::Minimum | AnimationProgress::NoData => Self::Fire,
+            AnimationProgress::Cancel => Self::Cancel,
+            AnimationProgress::Reset => Self::Reset,
+            AnimationProgress::None | AnimationProgress::Unknown(_) => Self::Unknown,
+        }
+    }
+}
+
+impl From<Activation> for CastOutcome {
+    #[inline]
+    fn from(activation: Activation) -> Self {
+        match activation {
+            Activation::CancelFire => Self::Fire,
+            Activation::CancelCancel => Self::Cancel,
+            Activation::Reset => Self::Reset,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A skill cast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cast {
+    /// Id of the casted skill.
+    pub skill_id: u32,
+
+    /// Time the cast started.
+    pub start: u64,
+
+    /// Time the cast ended.
+    ///
+    /// Uses the expected duration for casts with [`CastOutcome::Unknown`].
+    pub end: u64,
+
+    /// Outcome of the cast.
+    pub outcome: CastOutcome,
+
+    /// Whether the cast duration was scaled by speed modifiers like quickness.
+    pub quickness_scaled: bool,
+}
+
+impl Cast {
+    /// Returns the duration of the cast in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns the [`AnimationKind`] of the cast.
+    #[inline]
+    pub const fn kind(&self) -> AnimationKind {
+        AnimationKind::new(self.skill_id)
+    }
+}
+
+/// Skill casts of a log.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Casts {
+    /// Casts per agent id in order of start time.
+    pub by_agent: HashMap<u64, Vec<Cast>>,
+}
+
+impl Casts {
+    /// Returns the casts of the agent.
+    #[inline]
+    pub fn agent(&self, agent: u64) -> &[Cast] {
+        self.by_agent
+            .get(&agent)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Reconstructs skill [`Casts`] from the [`EventKind::AnimationStart`] and [`EventKind::AnimationStop`] events of the log.
+pub fn casts(log: &LogTransformed) -> Casts {
+    let mut tracker = CastTracker::default();
+    for event in &log.events {
+        tracker.process(event);
+    }
+    tracker.finish()
+}
+
+/// Reconstructs skill [`Casts`] from the legacy [`LegacyEventKind::Activation`] events of the log.
+///
+/// Starts with [`Activation::QuicknessUnused`] from logs before November 2019 are treated as quickness scaled starts.
+/// Animation events present in the log are respected as well.
+pub fn casts_legacy(log: &LogTransformedLegacy) -> Casts {
+    let mut tracker = CastTracker::default();
+    for event in &log.events {
+        match event {
+            LegacyEventKind::Activation(event) => match event.activation {
+                Activation::Start | Activation::QuicknessUnused => tracker.start(
+                    event.agent.id,
+                    event.skill_id,
+                    event.time,
+                    event.duration,
+                    event.activation == Activation::QuicknessUnused,
+                ),
+                Activation::CancelFire | Activation::CancelCancel | Activation::Reset => tracker
+                    .stop(
+                        event.agent.id,
+                        event.skill_id,
+                        event.time,
+                        event.activation.into(),
+                        event.duration != event.scaled_duration,
+                    ),
+                _ => {}
+            },
+            LegacyEventKind::Event(event) => tracker.process(event),
+            _ => {}
+        }
+    }
+    tracker.finish()
+}
+
+/// Pairs cast starts with their stops.
+#[derive(Debug, Default)]
+struct CastTracker {
+    casts: Casts,
+    pending: HashMap<u64, Pending>,
+}
+
+impl CastTracker {
+    /// Processes an [`EventKind`].
+    fn process(&mut self, event: &EventKind) {
+        match event {
+            EventKind::AnimationStart(event) => self.start(
+                event.agent.id,
+                event.skill_id,
+                event.time,
+                event.duration_control,
+                false,
+            ),
+            EventKind::AnimationStop(event) => self.stop(
+                event.agent.id,
+                event.skill_id,
+                event.time,
+                event.progress.into(),
+                event.duration_scaled != event.duration_unscaled,
+            ),
+            _ => {}
+        }
+    }
+
+    /// Starts a cast, ending a previous cast of the agent without a stop.
+    fn start(&mut self, agent: u64, skill_id: u32, time: u64, expected: i32, scaled: bool) {
+        if let Some(pending) = self.pending.remove(&agent) {
+            let end = pending.expected_end().min(time);
+            self.push(agent, pending.into_cast(end, CastOutcome::Unknown, false));
+        }
+        self.pending.insert(
+            agent,
+            Pending {
+                skill_id,
+                start: time,
+                expected,
+                scaled,
+            },
+        );
+    }
+
+    /// Stops the pending cast of the agent.
+    ///
+    /// Stops without a matching start are ignored.
+    fn stop(&mut self, agent: u64, skill_id: u32, time: u64, outcome: CastOutcome, scaled: bool) {
+        if self
+            .pending
+            .get(&agent)
+            .is_some_and(|pending| pending.skill_id == skill_id)
+        {
+            if let Some(pending) = self.pending.remove(&agent) {
+                self.push(agent, pending.into_cast(time, outcome, scaled));
+            }
+        }
+    }
+
+    /// Adds a finished cast.
+    fn push(&mut self, agent: u64, cast: Cast) {
+        self.casts.by_agent.entry(agent).or_default().push(cast);
+    }
+
+    /// Ends all pending casts with their expected duration.
+    fn finish(mut self) -> Casts {
+        for (agent, pending) in std::mem::take(&mut self.pending) {
+            let end = pending.expected_end();
+            self.push(agent, pending.into_cast(end, CastOutcome::Unknown, false));
+        }
+        for casts in self.casts.by_agent.values_mut() {
+            casts.sort_by_key(|cast| cast.start);
+        }
+        self.casts
+    }
+}
+
+/// A cast without a stop yet.
+#[derive(Debug, Clone)]
+struct Pending {
+    skill_id: u32,
+    start: u64,
+    expected: i32,
+
+    /// Whether the start was already scaled by quickness.
+    scaled: bool,
+}
+
+impl Pending {
+    /// Returns the expected end time.
+    #[inline]
+    fn expected_end(&self) -> u64 {
+        self.start + self.expected.max(0) as u64
+    }
+
+    /// Converts into a finished [`Cast`].
+    #[inline]
+    fn into_cast(self, end: u64, outcome: CastOutcome, quickness_scaled: bool) -> Cast {
+        Cast {
+            skill_id: self.skill_id,
+            start: self.start,
+            end,
+            outcome,
+            quickness_scaled: self.scaled || quickness_scaled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, StateChange, fixture};
+
+    fn animation(time: u64, skill_id: u32, statechange: StateChange, value: i32) -> Event {
+        Event {
+            skill_id,
+            value,
+            buff_dmg: value,
+            ..fixture::statechange(time, 1, statechange)
+        }
+    }
+
+    fn activation(time: u64, skill_id: u32, activation: Activation, value: i32) -> Event {
+        Event {
+            skill_id,
+            value,
+            buff_dmg: value,
+            is_activation: activation.into(),
+            ..fixture::statechange(time, 1, StateChange::Combat)
+        }
+    }
+
+    #[test]
+    fn animation_casts() {
+        let log = fixture::log_transformed(vec![
+            animation(0, 10, StateChange::AnimationStart, 500).into(),
+            Event {
+                buff_dmg: 800,
+                is_activation: AnimationProgress::Minimum.into(),
+                ..animation(500, 10, StateChange::AnimationStop, 500)
+            }
+            .into(),
+            animation(1000, 20, StateChange::AnimationStart, 1000).into(),
+            Event {
+                is_activation: AnimationProgress::Cancel.into(),
+                ..animation(1200, 30, StateChange::AnimationStop, 200)
+            }
+            .into(),
+            animation(1500, 30, StateChange::AnimationStart, 300).into(),
+        ]);
+
+        let casts = casts(&log);
+        assert_eq!(
+            casts.agent(1),
+            [
+                Cast {
+                    skill_id: 10,
+                    start: 0,
+                    end: 500,
+                    outcome: CastOutcome::Fire,
+                    quickness_scaled: true,
+                },
+                Cast {
+                    skill_id: 20,
+                    start: 1000,
+                    end: 1500,
+                    outcome: CastOutcome::Unknown,
+                    quickness_scaled: false,
+                },
+                Cast {
+                    skill_id: 30,
+                    start: 1500,
+                    end: 1800,
+                    outcome: CastOutcome::Unknown,
+                    quickness_scaled: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_casts() {
+        let log = fixture::log_transformed(vec![
+            activation(0, 10, Activation::Start, 1000).into_legacy(),
+            activation(400, 10, Activation::CancelCancel, 400).into_legacy(),
+            activation(1000, 20, Activation::Start, 500).into_legacy(),
+            activation(1500, 20, Activation::Reset, 500).into_legacy(),
+        ]);
+
+        let casts = casts_legacy(&log);
+        assert_eq!(
+            casts
+                .agent(1)
+                .iter()
+                .map(|cast| (cast.skill_id, cast.duration(), cast.outcome))
+                .collect::<Vec<_>>(),
+            [
+                (10, 400, CastOutcome::Cancel),
+                (20, 500, CastOutcome::Reset)
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_quickness_casts() {
+        let log = fixture::log_transformed(vec![
+            activation(0, 10, Activation::QuicknessUnused, 660).into_legacy(),
+            activation(660, 10, Activation::CancelFire, 660).into_legacy(),
+            activation(1000, 20, Activation::QuicknessUnused, 500).into_legacy(),
+        ]);
+
+        let casts = casts_legacy(&log);
+        assert_eq!(
+            casts
+                .agent(1)
+                .iter()
+                .map(|cast| (
+                    cast.skill_id,
+                    cast.duration(),
+                    cast.outcome,
+                    cast.quickness_scaled
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (10, 660, CastOutcome::Fire, true),
+                (20, 500, CastOutcome::Unknown, true)
+            ]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{CombatEvent, CombatResult, EventKind, LogIndex, LogTransformed};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Aggregated damage statistics.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DamageStats {
+    /// Strike damage dealt to health.
+    pub strike_damage: i64,
+
+    /// Condition (buff) damage dealt to health.
+    pub condition_damage: i64,
+
+    /// Damage dealt to barrier.
+    pub barrier_damage: i64,
+
+    /// Health damage dealt to downed targets.
+    ///
+    /// This is included in strike and condition damage.
+    pub downed_damage: i64,
+
+    /// Amount of strike hits.
+    pub hits: u64,
+
+    /// Amount of critical strike hits.
+    pub crits: u64,
+
+    /// Amount of glancing strike hits.
+    pub glances: u64,
+
+    /// Amount of strike hits while flanking.
+    pub flanking: u64,
+}
+
+impl DamageStats {
+    /// Returns the total damage dealt to health.
+    #[inline]
+    pub fn health_damage(&self) -> i64 {
+        self.strike_damage + self.condition_damage
+    }
+
+    /// Returns the total damage dealt to health and barrier.
+    #[inline]
+    pub fn total_damage(&self) -> i64 {
+        self.health_damage() + self.barrier_damage
+    }
+
+    /// Returns the ratio of critical strike hits.
+    #[inline]
+    pub fn crit_rate(&self) -> f64 {
+        self.rate(self.crits)
+    }
+
+    /// Returns the ratio of glancing strike hits.
+    #[inline]
+    pub fn glance_rate(&self) -> f64 {
+        self.rate(self.glances)
+    }
+
+    /// Returns the ratio of strike hits while flanking.
+    #[inline]
+    pub fn flanking_rate(&self) -> f64 {
+        self.rate(self.flanking)
+    }
+
+    /// Returns the ratio of the given count to strike hits.
+    #[inline]
+    fn rate(&self, count: u64) -> f64 {
+        if self.hits > 0 {
+            count as f64 / self.hits as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Adds a [`CombatEvent`] to the statistics.
+    pub fn add(&mut self, event: &CombatEvent) {
+        let (health, is_strike) = if event.result.is_strike_damage() && !event.is_buff {
+            (event.non_shield_strike_damage() as i64, true)
+        } else if event.result.is_buff_damage() {
+            (event.non_shield_buff_damage() as i64, false)
+        } else {
+            return;
+        };
+
+        if is_strike {
+            self.strike_damage += health;
+            self.hits += 1;
+            match event.result {
+                CombatResult::StrikeDamageCrit => self.crits += 1,
+                CombatResult::StrikeDamageGlance => self.glances += 1,
+                _ => {}
+            }
+            if event.common.is_flanking != 0 {
+                self.flanking += 1;
+            }
+        } else {
+            self.condition_damage += health;
+        }
+
+        self.barrier_damage += event.shield_damage as i64;
+        if event.target_downed {
+            self.downed_damage += health;
+        }
+    }
+}
+
+/// Damage statistics of a log.
+///
+/// Damage of minions is attributed to their top-most master.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Damage {
+    /// Start time of the first time window.
+    pub start: u64,
+
+    /// Length of a time window in milliseconds.
+    pub window: u64,
+
+    /// Damage dealt per source agent id.
+    pub by_source: HashMap<u64, DamageStats>,
+
+    /// Damage taken per target agent id.
+    pub by_target: HashMap<u64, DamageStats>,
+
+    /// Damage dealt per pair of source and target agent id.
+    pub by_source_target: HashMap<(u64, u64), DamageStats>,
+
+    /// Damage dealt per source agent id in consecutive time windows.
+    pub timeline: HashMap<u64, Vec<i64>>,
+}
+
+impl Damage {
+    /// Default length of a time window in milliseconds.
+    pub const DEFAULT_WINDOW: u64 = 1000;
+
+    /// Returns the damage per second of the source in each time window.
+    pub fn dps(&self, source: u64) -> Vec<f64> {
+        let seconds = self.window as f64 / 1000.0;
+        self.timeline
+            .get(&source)
+            .map(|damage| {
+                damage
+                    .iter()
+                    .map(|damage| *damage as f64 / seconds)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Aggregates [`Damage`] statistics of the log with the [default window](Damage::DEFAULT_WINDOW).
+pub fn damage(log: &LogTransformed) -> Damage {
+    damage_with_window(log, Damage::DEFAULT_WINDOW)
+}
+
+/// Aggregates [`Damage`] statistics of the log with the given time window length in milliseconds.
+pub fn damage_with_window(log: &LogTransformed, window: u64) -> Damage {
+    let index = log.index();
+    let window = window.max(1);
+    let combat = || {
+        log.events.iter().filter_map(|event| match event {
+            EventKind::Combat(event) => Some(event),
+            _ => None,
+        })
+    };
+
+    let mut damage = Damage {
+        start: combat()
+            .map(|event| event.common.time)
+            .min()
+            .unwrap_or_default(),
+        window,
+        ..Damage::default()
+    };
+
+    for event in combat().filter(|event| event.result.is_health_damage()) {
+        let common = &event.common;
+        let source = attribute(&index, event);
+        let target = common.target.id;
+
+        damage.by_source.entry(source).or_default().add(event);
+        damage.by_target.entry(target).or_default().add(event);
+        damage
+            .by_source_target
+            .entry((source, target))
+            .or_default()
+            .add(event);
+
+        let slot = (common.time.saturating_sub(damage.start) / window) as usize;
+        let timeline = damage.timeline.entry(source).or_default();
+        if timeline.len() <= slot {
+            timeline.resize(slot + 1, 0);
+        }
+        let mut stats = DamageStats::default();
+        stats.add(event);
+        timeline[slot] += stats.total_damage();
+    }
+
+    damage
+}
+
+/// Returns the agent id the damage of the [`CombatEvent`] is attributed to.
+fn attribute(index: &LogIndex, event: &CombatEvent) -> u64 {
+    let source = &event.common.source;
+    index
+        .owner_at(source, event.common.time)
+        .map(|agent| agent.id)
+        .unwrap_or(source.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Agent, Event, StateChange, fixture};
+
+    fn agent(id: u64) -> Agent {
+        fixture::agent(id, &[&format!("Agent {id}")])
+    }
+
+    fn hit(time: u64, src: (u64, u16, u16), value: i32, result: CombatResult) -> Event {
+        Event {
+            src_instance_id: src.1,
+            src_master_instance_id: src.2,
+            dst_agent: 3,
+            dst_instance_id: 30,
+            value,
+            result: result.into(),
+            ..fixture::statechange(time, src.0, StateChange::Combat)
+        }
+    }
+
+    #[test]
+    fn aggregate() {
+        let log = fixture::log(
+            123,
+            vec![agent(1), agent(2), agent(3)],
+            vec![
+                hit(0, (1, 10, 0), 100, CombatResult::StrikeDamage),
+                Event {
+                    is_flanking: 1,
+                    ..hit(500, (1, 10, 0), 200, CombatResult::StrikeDamageCrit)
+                },
+                hit(1200, (2, 20, 10), 300, CombatResult::StrikeDamageGlance),
+                Event {
+                    value: 0,
+                    buff: 1,
+                    buff_dmg: 50,
+                    is_offcycle: 1,
+                    ..hit(2500, (1, 10, 0), 0, CombatResult::BuffDamageCycle)
+                },
+                Event {
+                    overstack_value: 40,
+                    ..hit(2600, (1, 10, 0), 100, CombatResult::StrikeDamage)
+                },
+                hit(2700, (1, 10, 0), 0, CombatResult::Evade),
+                Event {
+                    is_statechange: StateChange::HealthUpdate.into(),
+                    ..hit(3000, (1, 10, 0), 0, CombatResult::StrikeDamage)
+                },
+            ],
+        )
+        .into_transformed();
+
+        let damage = damage(&log);
+        let stats = &damage.by_source[&1];
+        assert_eq!(stats.strike_damage, 100 + 200 + 300 + 60);
+        assert_eq!(stats.condition_damage, 50);
+        assert_eq!(stats.barrier_damage, 40);
+        assert_eq!(stats.downed_damage, 50);
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.crits, 1);
+        assert_eq!(stats.glances, 1);
+        assert_eq!(stats.flanking_rate(), 0.25);
+        assert!(!damage.by_source.contains_key(&2));
+        assert_eq!(damage.by_target[&3].total_damage(), stats.total_damage());
+        assert_eq!(damage.timeline[&1], [300, 300, 150]);
+        assert_eq!(damage.dps(1), [300.0, 300.0, 150.0]);
+    }
+}
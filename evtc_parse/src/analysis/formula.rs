@@ -0,0 +1,360 @@
+use crate::{
+    EventKind, LogTransformed,
+    buff::{Attribute, BuffFormula},
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Active buffs and traits of an agent at a point in time.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuffSet {
+    /// Whether the agent is a player.
+    pub is_player: bool,
+
+    /// Amount of stacks per active buff id.
+    pub buffs: HashMap<u32, u32>,
+
+    /// Known trait ids of the agent.
+    pub traits: HashSet<u32>,
+}
+
+impl BuffSet {
+    /// Creates a new buff set with the given active buffs.
+    #[inline]
+    pub fn new(is_player: bool, buffs: HashMap<u32, u32>) -> Self {
+        Self {
+            is_player,
+            buffs,
+            traits: HashSet::new(),
+        }
+    }
+
+    /// Returns the amount of stacks of the buff.
+    #[inline]
+    pub fn stacks(&self, buff: u32) -> u32 {
+        self.buffs.get(&buff).copied().unwrap_or_default()
+    }
+
+    /// Checks whether the buff is active.
+    #[inline]
+    pub fn has_buff(&self, buff: u32) -> bool {
+        self.stacks(buff) > 0
+    }
+
+    /// Checks whether the trait is known.
+    #[inline]
+    pub fn has_trait(&self, id: u32) -> bool {
+        self.traits.contains(&id)
+    }
+}
+
+/// Attribute modifier contributed by a buff formula.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Modifier {
+    /// Buff id the modifier originates from.
+    pub buff: u32,
+
+    /// Group the modifier stacks additively in.
+    ///
+    /// Defaults to the buff id for buffs without an additive group.
+    pub group: u32,
+
+    /// Modified attribute.
+    pub attribute: Attribute,
+
+    /// Total value of the modifier.
+    ///
+    /// Percentages for damage modifiers.
+    pub value: f32,
+}
+
+/// Attribute modifiers resulting from a [`BuffSet`].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Modifiers {
+    /// Individual modifiers per buff formula.
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Modifiers {
+    /// Returns the total value of the attribute.
+    pub fn attribute(&self, attribute: Attribute) -> f32 {
+        self.modifiers
+            .iter()
+            .filter(|modifier| modifier.attribute == attribute)
+            .map(|modifier| modifier.value)
+            .sum()
+    }
+
+    /// Returns the modifiers of the attribute.
+    pub fn by_attribute(&self, attribute: Attribute) -> impl Iterator<Item = &Modifier> {
+        self.modifiers
+            .iter()
+            .filter(move |modifier| modifier.attribute == attribute)
+    }
+
+    /// Returns the multiplier for outgoing strike damage.
+    #[inline]
+    pub fn outgoing_strike(&self) -> f32 {
+        self.multiplier(Attribute::PhysInc)
+    }
+
+    /// Returns the multiplier for outgoing condition damage.
+    #[inline]
+    pub fn outgoing_condition(&self) -> f32 {
+        self.multiplier(Attribute::CondInc)
+    }
+
+    /// Returns the multiplier for incoming strike damage.
+    #[inline]
+    pub fn incoming_strike(&self) -> f32 {
+        self.multiplier(Attribute::PhysRec)
+    }
+
+    /// Returns the multiplier for incoming condition damage.
+    #[inline]
+    pub fn incoming_condition(&self) -> f32 {
+        self.multiplier(Attribute::CondRec)
+    }
+
+    /// Returns the multiplier for a percentage attribute.
+    ///
+    /// Modifiers within the same group stack additively, different groups stack multiplicatively.
+    fn multiplier(&self, attribute: Attribute) -> f32 {
+        let mut groups = BTreeMap::<u32, f32>::new();
+        for modifier in self.by_attribute(attribute) {
+            *groups.entry(modifier.group).or_default() += modifier.value;
+        }
+        groups.values().map(|value| 1.0 + value / 100.0).product()
+    }
+}
+
+/// [`BuffFormula`] type with a flat value per stack.
+pub const FORMULA_FLAT: u32 = 0;
+
+/// Evaluates [`BuffFormula`]s for [`BuffSet`]s.
+///
+/// Only a subset of formulas is supported:
+/// - Formulas of type [`FORMULA_FLAT`] contribute their first parameter per stack to their first attribute.
+/// - Other formula types scale with values unknown to the log, like the agent level, and are not evaluated.
+/// - Conversion formulas with a second attribute are not evaluated, as base attributes are unknown.
+///
+/// Percentage modifiers of different buffs stack multiplicatively, unless the buffs share an additive group.
+#[derive(Debug, Default, Clone)]
+pub struct FormulaEvaluator {
+    formulas: HashMap<u32, Vec<BuffFormula>>,
+    groups: HashMap<u32, u32>,
+}
+
+impl FormulaEvaluator {
+    /// Creates a new evaluator without formulas.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new evaluator with the [`BuffFormula`]s of the log.
+    pub fn from_log(log: &LogTransformed) -> Self {
+        let mut evaluator = Self::new();
+        for event in &log.events {
+            if let EventKind::BuffFormula(formula) = event {
+                evaluator.add(formula.clone());
+            }
+        }
+        evaluator
+    }
+
+    /// Adds a formula.
+    #[inline]
+    pub fn add(&mut self, formula: BuffFormula) {
+        self.formulas
+            .entry(formula.skill_id)
+            .or_default()
+            .push(formula);
+    }
+
+    /// Adds an additive group of buffs.
+    ///
+    /// Percentage modifiers of buffs in the same group are summed before being applied.
+    pub fn add_group(&mut self, buffs: impl IntoIterator<Item = u32>) {
+        let mut buffs = buffs.into_iter();
+        if let Some(first) = buffs.next() {
+            let group = self.group(first);
+            for buff in buffs {
+                self.groups.insert(buff, group);
+            }
+        }
+    }
+
+    /// Returns the additive group of the buff.
+    #[inline]
+    pub fn group(&self, buff: u32) -> u32 {
+        self.groups.get(&buff).copied().unwrap_or(buff)
+    }
+
+    /// Returns the formulas of the buff.
+    #[inline]
+    pub fn formulas(&self, buff: u32) -> &[BuffFormula] {
+        self.formulas
+            .get(&buff)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Evaluates the modifiers for the agent with the given [`BuffSet`].
+    ///
+    /// Conditions on the source of a buff require the source [`BuffSet`].
+    /// Formulas with source conditions are skipped if the source is unknown.
+    pub fn evaluate(&self, agent: &BuffSet, source: Option<&BuffSet>) -> Modifiers {
+        let mut modifiers = Vec::new();
+        for (buff, stacks) in &agent.buffs {
+            for formula in self.formulas(*buff) {
+                if !Self::applies(formula, agent, source) {
+                    continue;
+                }
+                if let (Some(attribute), Some(value)) =
+                    (Self::attribute(formula), Self::value(formula, *stacks))
+                {
+                    modifiers.push(Modifier {
+                        buff: *buff,
+                        group: self.group(*buff),
+                        attribute,
+                        value,
+                    });
+                }
+            }
+        }
+        modifiers.sort_by_key(|modifier| (modifier.attribute, modifier.buff));
+        Modifiers { modifiers }
+    }
+
+    /// Returns the value of the formula for the amount of stacks.
+    fn value(formula: &BuffFormula, stacks: u32) -> Option<f32> {
+        match formula.formula {
+            FORMULA_FLAT => Some(formula.param1 * stacks as f32),
+            _ => None,
+        }
+    }
+
+    /// Returns the evaluated attribute of the formula.
+    fn attribute(formula: &BuffFormula) -> Option<Attribute> {
+        let attr2 = u16::try_from(formula.attr2).ok()?;
+        if Attribute::try_from(attr2).ok()? != Attribute::None {
+            return None;
+        }
+        match Attribute::try_from(u16::try_from(formula.attr1).ok()?).ok()? {
+            Attribute::None => None,
+            attribute => Some(attribute),
+        }
+    }
+
+    /// Checks whether the formula applies to the agent.
+    fn applies(formula: &BuffFormula, agent: &BuffSet, source: Option<&BuffSet>) -> bool {
+        if (formula.not_npc && !agent.is_player) || (formula.not_player && agent.is_player) {
+            return false;
+        }
+        if formula.is_unconditional() {
+            return true;
+        }
+        let check_self = (formula.trait_self == 0 || agent.has_trait(formula.trait_self))
+            && (formula.buff_self == 0 || agent.has_buff(formula.buff_self));
+        let check_src = formula.trait_src == 0 && formula.buff_src == 0
+            || source.is_some_and(|source| {
+                (formula.trait_src == 0 || source.has_trait(formula.trait_src))
+                    && (formula.buff_src == 0 || source.has_buff(formula.buff_src))
+            });
+        check_self && check_src
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formula(skill_id: u32, attr1: Attribute, param1: f32) -> BuffFormula {
+        BuffFormula {
+            skill_id,
+            formula: 0,
+            attr1: u16::from(attr1).into(),
+            attr2: 0,
+            param1,
+            param2: 0.0,
+            param3: 0.0,
+            trait_src: 0,
+            trait_self: 0,
+            buff_src: 0,
+            buff_self: 0,
+            content_reference: 0.0,
+            content_reference_type: 0,
+            not_npc: false,
+            not_player: false,
+            is_break: false,
+        }
+    }
+
+    #[test]
+    fn evaluate() {
+        let mut evaluator = FormulaEvaluator::new();
+        evaluator.add(formula(740, Attribute::Power, 30.0));
+        evaluator.add(formula(725, Attribute::PhysInc, 10.0));
+        evaluator.add(BuffFormula {
+            not_player: true,
+            ..formula(725, Attribute::Power, 100.0)
+        });
+        evaluator.add(BuffFormula {
+            buff_self: 717,
+            ..formula(1122, Attribute::PhysInc, 5.0)
+        });
+        evaluator.add(BuffFormula {
+            buff_src: 740,
+            ..formula(1122, Attribute::CondInc, 5.0)
+        });
+        evaluator.add(BuffFormula {
+            attr2: u16::from(Attribute::Toughness).into(),
+            ..formula(1122, Attribute::Power, 10.0)
+        });
+
+        let agent = BuffSet::new(true, [(740, 25), (725, 1), (1122, 1)].into());
+        let modifiers = evaluator.evaluate(&agent, None);
+        assert_eq!(modifiers.attribute(Attribute::Power), 750.0);
+        assert_eq!(modifiers.attribute(Attribute::PhysInc), 10.0);
+        assert_eq!(modifiers.attribute(Attribute::CondInc), 0.0);
+        assert_eq!(modifiers.outgoing_strike(), 1.1);
+
+        let agent = BuffSet::new(true, [(717, 1), (725, 1), (1122, 1)].into());
+        let source = BuffSet::new(false, [(740, 1)].into());
+        let modifiers = evaluator.evaluate(&agent, Some(&source));
+        assert_eq!(
+            modifiers
+                .by_attribute(Attribute::PhysInc)
+                .map(|modifier| modifier.buff)
+                .collect::<Vec<_>>(),
+            [725, 1122]
+        );
+        assert_eq!(modifiers.outgoing_strike(), 1.1 * 1.05);
+        assert_eq!(modifiers.outgoing_condition(), 1.05);
+        assert_eq!(modifiers.attribute(Attribute::Power), 0.0);
+    }
+
+    #[test]
+    fn groups() {
+        let mut evaluator = FormulaEvaluator::new();
+        evaluator.add(formula(725, Attribute::PhysInc, 10.0));
+        evaluator.add(formula(1122, Attribute::PhysInc, 5.0));
+        evaluator.add(formula(740, Attribute::PhysInc, 20.0));
+        evaluator.add(BuffFormula {
+            formula: 1,
+            ..formula(717, Attribute::PhysInc, 50.0)
+        });
+        evaluator.add_group([725, 1122]);
+
+        let agent = BuffSet::new(true, [(725, 1), (1122, 2), (740, 1), (717, 1)].into());
+        let modifiers = evaluator.evaluate(&agent, None);
+        assert_eq!(modifiers.modifiers.len(), 3);
+        assert_eq!(modifiers.outgoing_strike(), 1.2 * 1.2);
+    }
+}
@@ -0,0 +1,40 @@
+//! Analysis of parsed logs.
+//!
+//! Analyses operate on a [`LogTransformed`] with [`EventKind`](crate::EventKind)s.
+//!
+//! ```no_run
+//! use evtc_parse::analysis;
+//!
+//! let log = evtc_parse::parse_file("path/to/log.evtc").unwrap().into_transformed();
+//! let outcome = analysis::outcome(&log);
+//! println!("Success: {}, duration: {}ms", outcome.success, outcome.duration());
+//! ```
+
+mod buffs;
+mod casts;
+mod damage;
+mod formula;
+mod movement;
+mod outcome;
+mod replay;
+
+pub use self::{buffs::*, casts::*, damage::*, formula::*, movement::*, outcome::*, replay::*};
+
+use crate::{Agent, AgentKind, Encounter, LogTransformed};
+
+/// Returns the target [`Agent`]s of the log [`Encounter`].
+///
+/// Targets are NPCs with a species id belonging to the encounter.
+pub fn targets(log: &LogTransformed) -> Vec<&Agent> {
+    let encounter = log.encounter();
+    log.agents
+        .iter()
+        .filter(|agent| match agent.kind() {
+            AgentKind::Npc(species) => match encounter {
+                Encounter::Unknown(id) => species == id,
+                encounter => Encounter::from_species(species) == encounter,
+            },
+            _ => false,
+        })
+        .collect()
+}
@@ -0,0 +1,238 @@
+use crate::{EventKind, LogTransformed, Position};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A [`Position`] at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sample {
+    /// Time of the sample.
+    pub time: u64,
+
+    /// Position, velocity or facing direction.
+    pub position: Position,
+}
+
+/// Movement of an agent over time.
+///
+/// Positions are ingame coordinates in inches.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trajectory {
+    /// Position samples in order of time.
+    pub positions: Vec<Sample>,
+
+    /// Velocity samples in order of time.
+    pub velocities: Vec<Sample>,
+
+    /// Facing direction samples in order of time.
+    pub facings: Vec<Sample>,
+}
+
+impl Trajectory {
+    /// Returns the position at the given time.
+    ///
+    /// Positions between samples are linearly interpolated.
+    /// After the last sample the agent is assumed to stay in place.
+    /// Returns [`None`] before the first sample.
+    pub fn position_at(&self, time: u64) -> Option<Position> {
+        let index = self.positions.partition_point(|sample| sample.time <= time);
+        let prev = self.positions.get(index.checked_sub(1)?)?;
+        match self.positions.get(index) {
+            Some(next) => Some(interpolate(prev, next, time)),
+            None => Some(prev.position.clone()),
+        }
+    }
+
+    /// Returns the facing direction at the given time.
+    ///
+    /// Facing is not interpolated, the last known direction is returned.
+    /// Returns [`None`] before the first sample.
+    pub fn facing_at(&self, time: u64) -> Option<Position> {
+        let index = self.facings.partition_point(|sample| sample.time <= time);
+        self.facings
+            .get(index.checked_sub(1)?)
+            .map(|sample| sample.position.clone())
+    }
+
+    /// Returns the distance travelled between the given times in inches.
+    pub fn distance_travelled(&self, start: u64, end: u64) -> f32 {
+        let Some(mut prev) = self.position_at(start).or_else(|| {
+            self.positions
+                .first()
+                .filter(|sample| sample.time <= end)
+                .map(|sample| sample.position.clone())
+        }) else {
+            return 0.0;
+        };
+
+        let mut distance = 0.0;
+        for sample in self
+            .positions
+            .iter()
+            .filter(|sample| sample.time > start && sample.time < end)
+        {
+            distance += (&sample.position - &prev).len();
+            prev = sample.position.clone();
+        }
+        if let Some(last) = self.position_at(end) {
+            distance += (&last - &prev).len();
+        }
+        distance
+    }
+}
+
+/// Linearly interpolates between two samples.
+fn interpolate(prev: &Sample, next: &Sample, time: u64) -> Position {
+    let span = next.time.saturating_sub(prev.time);
+    if span == 0 {
+        return next.position.clone();
+    }
+    let factor = time.saturating_sub(prev.time) as f32 / span as f32;
+    let delta = &next.position - &prev.position;
+    &prev.position + &(&delta * factor)
+}
+
+/// Movement of agents in a log.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Movement {
+    /// Trajectory per agent id.
+    pub trajectories: HashMap<u64, Trajectory>,
+}
+
+impl Movement {
+    /// Returns the [`Trajectory`] of the agent.
+    #[inline]
+    pub fn trajectory(&self, agent: u64) -> Option<&Trajectory> {
+        self.trajectories.get(&agent)
+    }
+
+    /// Returns the position of the agent at the given time.
+    #[inline]
+    pub fn position_at(&self, agent: u64, time: u64) -> Option<Position> {
+        self.trajectory(agent)?.position_at(time)
+    }
+
+    /// Returns the facing direction of the agent at the given time.
+    #[inline]
+    pub fn facing_at(&self, agent: u64, time: u64) -> Option<Position> {
+        self.trajectory(agent)?.facing_at(time)
+    }
+
+    /// Returns the distance travelled by the agent between the given times in inches.
+    #[inline]
+    pub fn distance_travelled(&self, agent: u64, start: u64, end: u64) -> f32 {
+        self.trajectory(agent)
+            .map(|trajectory| trajectory.distance_travelled(start, end))
+            .unwrap_or_default()
+    }
+
+    /// Returns the distance between two agents at the given time in inches.
+    pub fn distance(&self, agent: u64, other: u64, time: u64) -> Option<f32> {
+        let pos = self.position_at(agent, time)?;
+        let other = self.position_at(other, time)?;
+        Some((&pos - &other).len())
+    }
+
+    /// Returns the distance between two agents sampled in steps between the given times.
+    ///
+    /// Times where either position is unknown are skipped.
+    pub fn distance_over_time(
+        &self,
+        agent: u64,
+        other: u64,
+        start: u64,
+        end: u64,
+        step: u64,
+    ) -> Vec<(u64, f32)> {
+        (start..=end)
+            .step_by(step.max(1) as usize)
+            .filter_map(|time| Some((time, self.distance(agent, other, time)?)))
+            .collect()
+    }
+}
+
+/// Builds the [`Movement`] of agents from [`EventKind::Position`], [`EventKind::Velocity`] and [`EventKind::Facing`] events of the log.
+pub fn movement(log: &LogTransformed) -> Movement {
+    let mut movement = Movement::default();
+    for event in &log.events {
+        let (event, samples): (_, fn(&mut Trajectory) -> &mut Vec<Sample>) = match event {
+            EventKind::Position(event) => (event, |trajectory| &mut trajectory.positions),
+            EventKind::Velocity(event) => (event, |trajectory| &mut trajectory.velocities),
+            EventKind::Facing(event) => (event, |trajectory| &mut trajectory.facings),
+            _ => continue,
+        };
+        let trajectory = movement.trajectories.entry(event.agent.id).or_default();
+        samples(trajectory).push(Sample {
+            time: event.time,
+            position: event.position.clone(),
+        });
+    }
+    for trajectory in movement.trajectories.values_mut() {
+        trajectory.positions.sort_by_key(|sample| sample.time);
+        trajectory.velocities.sort_by_key(|sample| sample.time);
+        trajectory.facings.sort_by_key(|sample| sample.time);
+    }
+    movement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, StateChange, fixture};
+
+    fn position(time: u64, agent: u64, statechange: StateChange, x: f32, y: f32) -> EventKind {
+        let [x0, x1, x2, x3] = x.to_le_bytes();
+        let [y0, y1, y2, y3] = y.to_le_bytes();
+        Event {
+            dst_agent: u64::from_le_bytes([x0, x1, x2, x3, y0, y1, y2, y3]),
+            ..fixture::statechange(time, agent, statechange)
+        }
+        .into()
+    }
+
+    #[test]
+    fn trajectory() {
+        let log = fixture::log_transformed(vec![
+            position(0, 1, StateChange::Position, 0.0, 0.0),
+            position(0, 1, StateChange::Facing, 1.0, 0.0),
+            position(0, 2, StateChange::Position, 0.0, 300.0),
+            position(1000, 1, StateChange::Position, 100.0, 0.0),
+            position(1500, 1, StateChange::Facing, 0.0, 1.0),
+            position(2000, 1, StateChange::Position, 100.0, 100.0),
+        ]);
+
+        let movement = movement(&log);
+        assert_eq!(
+            movement.position_at(1, 500),
+            Some(Position::new(50.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            movement.position_at(1, 1500),
+            Some(Position::new(100.0, 50.0, 0.0))
+        );
+        assert_eq!(
+            movement.position_at(1, 5000),
+            Some(Position::new(100.0, 100.0, 0.0))
+        );
+        assert_eq!(
+            movement.facing_at(1, 1000),
+            Some(Position::new(1.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            movement.facing_at(1, 1500),
+            Some(Position::new(0.0, 1.0, 0.0))
+        );
+        assert_eq!(movement.distance_travelled(1, 500, 1500), 100.0);
+        assert_eq!(movement.distance_travelled(1, 0, 5000), 200.0);
+        assert_eq!(movement.distance(1, 2, 0), Some(300.0));
+        assert_eq!(
+            movement.distance_over_time(1, 2, 1000, 2000, 1000),
+            [(1000, 316.22775), (2000, 223.6068)]
+        );
+        assert_eq!(movement.position_at(3, 0), None);
+    }
+}
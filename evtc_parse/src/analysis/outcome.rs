@@ -0,0 +1,259 @@
+use super::targets;
+use crate::{EventKind, LogTransformed, agent::BreakbarState};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Outcome of an encounter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Outcome {
+    /// Whether the encounter was successful.
+    pub success: bool,
+
+    /// Signal the success was determined from.
+    pub signal: Option<SuccessSignal>,
+
+    /// Start time of the encounter.
+    ///
+    /// Taken from [`EventKind::SquadCombatStart`], [`EventKind::InstanceStart`] or the first combat event.
+    pub start: u64,
+
+    /// End time of the encounter.
+    ///
+    /// This is the kill time for successful encounters,
+    /// otherwise taken from [`EventKind::SquadCombatEnd`] or the last combat event.
+    pub end: u64,
+
+    /// Time the encounter targets were killed, if successful.
+    pub kill_time: Option<u64>,
+}
+
+impl Outcome {
+    /// Returns the duration of the encounter in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Signal indicating a successful encounter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SuccessSignal {
+    /// All targets died.
+    TargetDead,
+
+    /// A reward was received.
+    Reward,
+
+    /// All targets died or reached the kill health threshold.
+    TargetHealth,
+}
+
+/// Health threshold at which a target is considered killed.
+///
+/// Some targets turn invulnerable at low health instead of dying.
+/// Health is given with `1.0` being max, so this considers targets at or below 0.1% killed.
+pub const KILL_HEALTH: f32 = 0.001;
+
+/// Determines the [`Outcome`] of the encounter in the log.
+///
+/// Success is determined from a [`EventKind::ChangeDead`] of every target, a [`EventKind::Reward`]
+/// or the target health at the end of the log.
+/// Encounters with multiple targets are only successful if all of them are dead or at [`KILL_HEALTH`].
+/// Low target health is disregarded if the target changes its [`BreakbarState`] afterwards, indicating it is still active.
+pub fn outcome(log: &LogTransformed) -> Outcome {
+    let targets = targets(log)
+        .into_iter()
+        .map(|agent| agent.id)
+        .collect::<HashSet<_>>();
+
+    let mut squad_start = None;
+    let mut instance_start = None;
+    let mut squad_end = None;
+    let mut first_combat = None;
+    let mut last_combat = None;
+    let mut deaths = HashMap::new();
+    let mut reward = None;
+    let mut health = HashMap::new();
+    let mut breakbar = HashMap::new();
+
+    for event in &log.events {
+        match event {
+            EventKind::SquadCombatStart(event) => {
+                squad_start.get_or_insert(event.time);
+            }
+            EventKind::InstanceStart { time, start } => {
+                instance_start.get_or_insert(time.saturating_sub(*start));
+            }
+            EventKind::SquadCombatEnd(event) => squad_end = Some(event.time),
+            EventKind::Combat(event) => {
+                first_combat.get_or_insert(event.common.time);
+                last_combat = Some(event.common.time);
+            }
+            EventKind::ChangeDead(event) if targets.contains(&event.agent.id) => {
+                deaths.insert(event.agent.id, event.time);
+            }
+            EventKind::Reward(event) => {
+                reward.get_or_insert(event.time);
+            }
+            EventKind::HealthUpdate(event) if targets.contains(&event.agent.id) => {
+                health.insert(event.agent.id, (event.time, event.health));
+            }
+            EventKind::BreakbarState(event)
+                if targets.contains(&event.agent.id) && event.state != BreakbarState::None =>
+            {
+                breakbar.insert(event.agent.id, event.time);
+            }
+            _ => {}
+        }
+    }
+
+    // every target needs to be dead or at kill health without being active afterwards
+    let mut all_dead = true;
+    let mut targets_down = (!targets.is_empty()).then_some(0);
+    for id in &targets {
+        let time = if let Some(time) = deaths.get(id) {
+            Some(*time)
+        } else {
+            all_dead = false;
+            health
+                .get(id)
+                .filter(|(updated, health)| {
+                    *health <= KILL_HEALTH && breakbar.get(id).is_none_or(|time| time <= updated)
+                })
+                .map(|(time, _)| *time)
+        };
+        targets_down = targets_down.zip(time).map(|(max, time)| max.max(time));
+    }
+
+    let (signal, kill_time) = match (targets_down, reward) {
+        (Some(time), _) if all_dead => (Some(SuccessSignal::TargetDead), Some(time)),
+        (_, Some(time)) => (Some(SuccessSignal::Reward), Some(time)),
+        (Some(time), None) => (Some(SuccessSignal::TargetHealth), Some(time)),
+        (None, None) => (None, None),
+    };
+
+    let start = squad_start
+        .or(instance_start)
+        .or(first_combat)
+        .unwrap_or_default();
+    let end = kill_time.or(squad_end).or(last_combat).unwrap_or(start);
+
+    Outcome {
+        success: signal.is_some(),
+        signal,
+        start,
+        end,
+        kill_time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, StateChange, fixture};
+
+    fn log(events: Vec<Event>) -> LogTransformed {
+        fixture::log(15438, vec![fixture::npc(1, "Vale Guardian", 15438)], events)
+            .into_transformed()
+    }
+
+    fn event(time: u64, statechange: StateChange, src_agent: u64, dst_agent: u64) -> Event {
+        Event {
+            dst_agent,
+            ..fixture::statechange(time, src_agent, statechange)
+        }
+    }
+
+    #[test]
+    fn kill() {
+        let log = log(vec![
+            event(100, StateChange::SquadCombatStart, 0, 0),
+            event(150, StateChange::Combat, 2, 1),
+            event(5000, StateChange::ChangeDead, 1, 0),
+            event(6000, StateChange::SquadCombatEnd, 0, 0),
+        ]);
+        let outcome = outcome(&log);
+        assert!(outcome.success);
+        assert_eq!(outcome.signal, Some(SuccessSignal::TargetDead));
+        assert_eq!(outcome.kill_time, Some(5000));
+        assert_eq!(outcome.duration(), 4900);
+    }
+
+    #[test]
+    fn wipe() {
+        let log = log(vec![
+            event(100, StateChange::SquadCombatStart, 0, 0),
+            event(2000, StateChange::HealthUpdate, 1, 50),
+            event(3000, StateChange::HealthUpdate, 1, 0),
+            event(3500, StateChange::BreakbarState, 1, 0),
+            event(4000, StateChange::HealthUpdate, 1, 0),
+            event(4500, StateChange::BreakbarState, 1, 0),
+            event(6000, StateChange::SquadCombatEnd, 0, 0),
+        ]);
+        let outcome = outcome(&log);
+        assert!(!outcome.success);
+        assert_eq!(outcome.kill_time, None);
+        assert_eq!(outcome.end, 6000);
+    }
+
+    #[test]
+    fn health() {
+        let log = log(vec![
+            event(0, StateChange::InstanceStart, 100, 0),
+            event(2000, StateChange::HealthUpdate, 1, 50),
+            event(3000, StateChange::HealthUpdate, 1, 0),
+            event(4000, StateChange::SquadCombatEnd, 0, 0),
+        ]);
+        let outcome = outcome(&log);
+        assert_eq!(outcome.signal, Some(SuccessSignal::TargetHealth));
+        assert_eq!(outcome.start, 0);
+        assert_eq!(outcome.end, 3000);
+    }
+
+    #[test]
+    fn low_health() {
+        let above = log(vec![
+            event(0, StateChange::InstanceStart, 100, 0),
+            event(2000, StateChange::HealthUpdate, 1, 50),
+            event(4000, StateChange::SquadCombatEnd, 0, 0),
+        ]);
+        assert!(!outcome(&above).success);
+
+        let low = log(vec![
+            event(0, StateChange::InstanceStart, 100, 0),
+            event(2000, StateChange::HealthUpdate, 1, 500),
+            event(3000, StateChange::HealthUpdate, 1, 8),
+            event(4000, StateChange::SquadCombatEnd, 0, 0),
+        ]);
+        let outcome = outcome(&low);
+        assert_eq!(outcome.signal, Some(SuccessSignal::TargetHealth));
+        assert_eq!(outcome.kill_time, Some(3000));
+    }
+
+    #[test]
+    fn multi_target_wipe() {
+        let log = fixture::log(
+            16088,
+            vec![
+                fixture::npc(1, "Berg", 16088),
+                fixture::npc(2, "Zane", 16137),
+                fixture::npc(3, "Narella", 16125),
+            ],
+            vec![
+                event(0, StateChange::InstanceStart, 100, 0),
+                event(2000, StateChange::ChangeDead, 1, 0),
+                event(3000, StateChange::HealthUpdate, 2, 0),
+                event(4000, StateChange::SquadCombatEnd, 0, 0),
+            ],
+        )
+        .into_transformed();
+        let outcome = outcome(&log);
+        assert!(!outcome.success);
+        assert_eq!(outcome.signal, None);
+        assert_eq!(outcome.end, 4000);
+    }
+}
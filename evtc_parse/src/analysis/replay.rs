@@ -0,0 +1,461 @@
+use super::{Trajectory, movement};
+use crate::{EventKind, LogTransformed, Position, marker::SquadMarker};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Combat replay document for map visualization.
+///
+/// With the `serde` feature the replay can be serialized into formats like JSON or MessagePack.
+/// Spans without a matching remove end at the end of the replay.
+/// Tracking ids reused later in the log start a new span, ending the previous one if still open.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Replay {
+    /// Time of the first replayed event.
+    pub start: u64,
+
+    /// Time of the last replayed event.
+    pub end: u64,
+
+    /// Trajectory per agent id.
+    pub agents: HashMap<u64, Trajectory>,
+
+    /// Agent markers in order of time.
+    pub agent_markers: Vec<AgentMarkerSpan>,
+
+    /// Squad markers in order of time.
+    pub squad_markers: Vec<SquadMarkerSpan>,
+
+    /// Ground effects per tracking id, each in order of time.
+    ///
+    /// Keyed by tracking id to keep every span of a reused tracking id.
+    pub ground_effects: HashMap<u32, Vec<GroundEffectSpan>>,
+
+    /// Missiles per tracking id, each in order of time.
+    ///
+    /// Keyed by tracking id to keep every span of a reused tracking id.
+    pub missiles: HashMap<u32, Vec<MissileSpan>>,
+}
+
+/// Marker above an agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AgentMarkerSpan {
+    /// Agent id.
+    pub agent: u64,
+
+    /// Marker id.
+    pub marker: i32,
+
+    /// Whether the marker is a commander tag.
+    pub commander: bool,
+
+    /// Time the marker was added.
+    pub start: u64,
+
+    /// Time the marker was removed.
+    pub end: u64,
+}
+
+/// Squad marker placed on the ground.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SquadMarkerSpan {
+    /// Squad marker.
+    pub marker: SquadMarker,
+
+    /// Location of the marker.
+    pub position: Position,
+
+    /// Time the marker was placed.
+    pub start: u64,
+
+    /// Time the marker was removed.
+    pub end: u64,
+}
+
+/// Effect on the ground.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GroundEffectSpan {
+    /// Id of the effect.
+    pub effect_id: u32,
+
+    /// Source agent id.
+    pub source: u64,
+
+    /// Location of the effect.
+    pub location: Position,
+
+    /// Orientation of the effect.
+    pub orientation: Position,
+
+    /// Scale of the effect.
+    pub scale: f32,
+
+    /// Time the effect was created.
+    pub start: u64,
+
+    /// Time the effect was removed or expired.
+    pub end: u64,
+}
+
+/// Missile with its launches.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MissileSpan {
+    /// Associated skill id.
+    pub skill_id: u32,
+
+    /// Source agent id.
+    pub source: u64,
+
+    /// Location the missile was created at.
+    pub location: Position,
+
+    /// Launches and relaunches in order of time.
+    pub launches: Vec<MissileLaunchPoint>,
+
+    /// Time the missile was created.
+    pub start: u64,
+
+    /// Time the missile was removed.
+    pub end: u64,
+}
+
+/// Launch of a missile.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MissileLaunchPoint {
+    /// Time of the launch.
+    pub time: u64,
+
+    /// Location the missile was launched from.
+    pub from: Position,
+
+    /// Target location.
+    pub to: Position,
+
+    /// Target agent id, if any.
+    pub target: Option<u64>,
+
+    /// Missile speed.
+    pub speed: i16,
+}
+
+/// Builds a [`Replay`] from the log.
+///
+/// Ground effects and missiles are paired with their removal by tracking id.
+pub fn replay(log: &LogTransformed) -> Replay {
+    let mut builder = ReplayBuilder {
+        replay: Replay {
+            agents: movement(log).trajectories,
+            ..Replay::default()
+        },
+        ..ReplayBuilder::default()
+    };
+    for event in &log.events {
+        builder.process(event);
+    }
+    builder.finish()
+}
+
+/// Pairs creations with their removals.
+#[derive(Debug, Default)]
+struct ReplayBuilder {
+    replay: Replay,
+    start: Option<u64>,
+    end: u64,
+    agent_markers: HashMap<u64, usize>,
+    squad_markers: HashMap<SquadMarker, usize>,
+}
+
+impl ReplayBuilder {
+    /// Processes the next event.
+    fn process(&mut self, event: &EventKind) {
+        match event {
+            EventKind::AgentMarker(event) => {
+                self.time(event.time);
+                if let Some(index) = self.agent_markers.remove(&event.agent.id) {
+                    self.replay.agent_markers[index].end = event.time;
+                }
+                if !event.is_remove() {
+                    self.agent_markers
+                        .insert(event.agent.id, self.replay.agent_markers.len());
+                    self.replay.agent_markers.push(AgentMarkerSpan {
+                        agent: event.agent.id,
+                        marker: event.marker,
+                        commander: event.is_commander(),
+                        start: event.time,
+                        end: u64::MAX,
+                    });
+                }
+            }
+            EventKind::SquadMarker(event) => {
+                self.time(event.time);
+                if let Some(index) = self.squad_markers.remove(&event.marker) {
+                    self.replay.squad_markers[index].end = event.time;
+                }
+                if !event.is_remove() {
+                    self.squad_markers
+                        .insert(event.marker, self.replay.squad_markers.len());
+                    self.replay.squad_markers.push(SquadMarkerSpan {
+                        marker: event.marker,
+                        position: event.position.clone(),
+                        start: event.time,
+                        end: u64::MAX,
+                    });
+                }
+            }
+            EventKind::EffectGroundCreate(event) => {
+                self.time(event.time);
+                let expires = if event.duration > 0 {
+                    event.time + event.duration as u64
+                } else {
+                    u64::MAX
+                };
+                let spans = self
+                    .replay
+                    .ground_effects
+                    .entry(event.tracking_id)
+                    .or_default();
+                if let Some(effect) = spans.last_mut() {
+                    effect.end = effect.end.min(event.time);
+                }
+                spans.push(GroundEffectSpan {
+                    effect_id: event.effect_id,
+                    source: event.source.id,
+                    location: event.location.clone(),
+                    orientation: event.orientation.clone(),
+                    scale: event.scale,
+                    start: event.time,
+                    end: expires,
+                });
+            }
+            EventKind::EffectGroundRemove(event) => {
+                self.time(event.time);
+                if let Some(effect) = self.ground_effect(event.tracking_id) {
+                    effect.end = effect.end.min(event.time);
+                }
+            }
+            EventKind::MissileCreate(event) => {
+                self.time(event.time);
+                let spans = self.replay.missiles.entry(event.tracking_id).or_default();
+                if let Some(missile) = spans.last_mut() {
+                    missile.end = missile.end.min(event.time);
+                }
+                spans.push(MissileSpan {
+                    skill_id: event.skill_id,
+                    source: event.source.id,
+                    location: event.location.clone(),
+                    launches: Vec::new(),
+                    start: event.time,
+                    end: u64::MAX,
+                });
+            }
+            EventKind::MissileLaunch(event) => {
+                self.time(event.time);
+                if let Some(missile) = self.missile(event.tracking_id) {
+                    missile.launches.push(MissileLaunchPoint {
+                        time: event.time,
+                        from: event.current_location.clone(),
+                        to: event.target_location.clone(),
+                        target: (event.target.id != 0).then_some(event.target.id),
+                        speed: event.speed,
+                    });
+                }
+            }
+            EventKind::MissileRemove(event) => {
+                self.time(event.time);
+                if let Some(missile) = self.missile(event.tracking_id) {
+                    missile.end = event.time;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the latest ground effect span with the tracking id.
+    fn ground_effect(&mut self, tracking_id: u32) -> Option<&mut GroundEffectSpan> {
+        self.replay.ground_effects.get_mut(&tracking_id)?.last_mut()
+    }
+
+    /// Returns the latest missile span with the tracking id, if not removed yet.
+    fn missile(&mut self, tracking_id: u32) -> Option<&mut MissileSpan> {
+        self.replay
+            .missiles
+            .get_mut(&tracking_id)?
+            .last_mut()
+            .filter(|missile| missile.end == u64::MAX)
+    }
+
+    /// Registers the time of a replayed event.
+    fn time(&mut self, time: u64) {
+        self.start = Some(self.start.map_or(time, |start| start.min(time)));
+        self.end = self.end.max(time);
+    }
+
+    /// Ends the replay, closing open spans at its end.
+    fn finish(mut self) -> Replay {
+        let bounds = self
+            .replay
+            .agents
+            .values()
+            .filter_map(|trajectory| {
+                let first = trajectory.positions.first()?;
+                let last = trajectory.positions.last()?;
+                Some((first.time, last.time))
+            })
+            .collect::<Vec<_>>();
+        for (first, last) in bounds {
+            self.time(first);
+            self.time(last);
+        }
+
+        let mut replay = self.replay;
+        replay.start = self.start.unwrap_or_default();
+        replay.end = self.end;
+
+        let end = replay.end;
+        let close = |value: &mut u64| *value = (*value).min(end);
+        replay
+            .agent_markers
+            .iter_mut()
+            .for_each(|marker| close(&mut marker.end));
+        replay
+            .squad_markers
+            .iter_mut()
+            .for_each(|marker| close(&mut marker.end));
+        replay
+            .ground_effects
+            .values_mut()
+            .flatten()
+            .for_each(|effect| close(&mut effect.end));
+        replay
+            .missiles
+            .values_mut()
+            .flatten()
+            .for_each(|missile| close(&mut missile.end));
+        replay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, StateChange, fixture};
+
+    fn event(time: u64, statechange: StateChange) -> Event {
+        fixture::statechange(time, 0, statechange)
+    }
+
+    fn log() -> LogTransformed {
+        fixture::log_transformed(
+            [
+                Event {
+                    src_agent: 1,
+                    value: 42,
+                    buff: 1,
+                    ..event(0, StateChange::Marker)
+                },
+                Event {
+                    skill_id: 3,
+                    ..event(100, StateChange::SquadMarker)
+                },
+                Event {
+                    src_agent: 2,
+                    skill_id: 99,
+                    pad61: 7,
+                    ..event(200, StateChange::MissileCreate)
+                },
+                Event {
+                    src_agent: 2,
+                    skill_id: 1234,
+                    pad61: 5,
+                    ..event(300, StateChange::EffectGroundCreate)
+                },
+                Event {
+                    src_agent: 1,
+                    ..event(500, StateChange::Marker)
+                },
+                Event {
+                    pad61: 5,
+                    ..event(600, StateChange::EffectGroundRemove)
+                },
+                Event {
+                    pad61: 7,
+                    ..event(800, StateChange::MissileRemove)
+                },
+                Event {
+                    pad61: 8,
+                    ..event(900, StateChange::MissileRemove)
+                },
+                Event {
+                    src_agent: 3,
+                    skill_id: 98,
+                    pad61: 7,
+                    ..event(900, StateChange::MissileCreate)
+                },
+                event(1000, StateChange::Position),
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn spans() {
+        let replay = replay(&log());
+        assert_eq!((replay.start, replay.end), (0, 1000));
+        assert_eq!(
+            replay.agent_markers,
+            [AgentMarkerSpan {
+                agent: 1,
+                marker: 42,
+                commander: true,
+                start: 0,
+                end: 500,
+            }]
+        );
+        assert_eq!(replay.squad_markers[0].marker, SquadMarker::Square);
+        assert_eq!(
+            (replay.squad_markers[0].start, replay.squad_markers[0].end),
+            (100, 1000)
+        );
+        assert_eq!(
+            replay.missiles[&7]
+                .iter()
+                .map(|missile| (missile.skill_id, missile.source, missile.start, missile.end))
+                .collect::<Vec<_>>(),
+            [(99, 2, 200, 800), (98, 3, 900, 1000)]
+        );
+        assert_eq!(
+            replay.ground_effects[&5]
+                .iter()
+                .map(|effect| (effect.effect_id, effect.source, effect.start, effect.end))
+                .collect::<Vec<_>>(),
+            [(1234, 2, 300, 600)]
+        );
+        assert!(replay.agents.contains_key(&0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let replay = replay(&log());
+        let json = serde_json::to_value(&replay).expect("failed to serialize replay");
+        assert_eq!(json["start"], 0);
+        assert_eq!(json["end"], 1000);
+        assert_eq!(json["agent_markers"][0]["marker"], 42);
+        assert_eq!(json["squad_markers"][0]["marker"], "Square");
+        assert_eq!(json["ground_effects"]["5"][0]["effect_id"], 1234);
+        assert_eq!(json["missiles"]["7"][1]["skill_id"], 98);
+        assert!(json["agents"]["0"].is_object());
+
+        let parsed: Replay = serde_json::from_value(json).expect("failed to deserialize replay");
+        assert_eq!(parsed, replay);
+    }
+}
@@ -0,0 +1,491 @@
+use crate::{AgentKind, EventKind, Log, LogTransformed, StateChange};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Known encounters.
+///
+/// Identified by the species id of the log target.
+/// Encounters with multiple targets are identified by any of their species ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Encounter {
+    /// Vale Guardian, Spirit Vale (W1).
+    ValeGuardian,
+
+    /// Gorseval the Multifarious, Spirit Vale (W1).
+    Gorseval,
+
+    /// Sabetha the Saboteur, Spirit Vale (W1).
+    Sabetha,
+
+    /// Slothasor, Salvation Pass (W2).
+    Slothasor,
+
+    /// Bandit Trio, Salvation Pass (W2).
+    BanditTrio,
+
+    /// Matthias Gabrel, Salvation Pass (W2).
+    Matthias,
+
+    /// Escort, Stronghold of the Faithful (W3).
+    Escort,
+
+    /// Keep Construct, Stronghold of the Faithful (W3).
+    KeepConstruct,
+
+    /// Twisted Castle, Stronghold of the Faithful (W3).
+    TwistedCastle,
+
+    /// Xera, Stronghold of the Faithful (W3).
+    Xera,
+
+    /// Cairn the Indomitable, Bastion of the Penitent (W4).
+    Cairn,
+
+    /// Mursaat Overseer, Bastion of the Penitent (W4).
+    MursaatOverseer,
+
+    /// Samarog, Bastion of the Penitent (W4).
+    Samarog,
+
+    /// Deimos, Bastion of the Penitent (W4).
+    Deimos,
+
+    /// Soulless Horror, Hall of Chains (W5).
+    SoullessHorror,
+
+    /// River of Souls, Hall of Chains (W5).
+    RiverOfSouls,
+
+    /// Broken King, Hall of Chains (W5).
+    BrokenKing,
+
+    /// Eater of Souls, Hall of Chains (W5).
+    EaterOfSouls,
+
+    /// Statue of Darkness, Hall of Chains (W5).
+    StatueOfDarkness,
+
+    /// Dhuum, Hall of Chains (W5).
+    Dhuum,
+
+    /// Conjured Amalgamate, Mythwright Gambit (W6).
+    ConjuredAmalgamate,
+
+    /// Twin Largos, Mythwright Gambit (W6).
+    TwinLargos,
+
+    /// Qadim, Mythwright Gambit (W6).
+    Qadim,
+
+    /// Cardinal Adina, The Key of Ahdashim (W7).
+    Adina,
+
+    /// Cardinal Sabir, The Key of Ahdashim (W7).
+    Sabir,
+
+    /// Qadim the Peerless, The Key of Ahdashim (W7).
+    QadimThePeerless,
+
+    /// Greer, Mount Balrior (W8).
+    Greer,
+
+    /// Decima, Mount Balrior (W8).
+    Decima,
+
+    /// Ura, Mount Balrior (W8).
+    Ura,
+
+    /// Icebrood Construct, Shiverpeaks Pass.
+    IcebroodConstruct,
+
+    /// Voice and Claw of the Fallen.
+    VoiceAndClaw,
+
+    /// Fraenir of Jormag.
+    Fraenir,
+
+    /// Boneskinner.
+    Boneskinner,
+
+    /// Whisper of Jormag.
+    WhisperOfJormag,
+
+    /// Cold War.
+    ColdWar,
+
+    /// Aetherblade Hideout.
+    AetherbladeHideout,
+
+    /// Xunlai Jade Junkyard.
+    XunlaiJadeJunkyard,
+
+    /// Kaineng Overlook.
+    KainengOverlook,
+
+    /// Harvest Temple.
+    HarvestTemple,
+
+    /// Old Lion's Court.
+    OldLionsCourt,
+
+    /// Cosmic Observatory.
+    CosmicObservatory,
+
+    /// Temple of Febe.
+    TempleOfFebe,
+
+    /// MAMA, Nightmare fractal.
+    Mama,
+
+    /// Siax the Corrupted, Nightmare fractal.
+    Siax,
+
+    /// Ensolyss of the Endless Torment, Nightmare fractal.
+    Ensolyss,
+
+    /// Skorvald, Shattered Observatory fractal.
+    Skorvald,
+
+    /// Artsariiv, Shattered Observatory fractal.
+    Artsariiv,
+
+    /// Arkk, Shattered Observatory fractal.
+    Arkk,
+
+    /// Ai, Keeper of the Peak, Sunqua Peak fractal.
+    Ai,
+
+    /// Kanaxai, Silent Surf fractal.
+    Kanaxai,
+
+    /// Special Forces Training Area golem with the given species id.
+    Golem(u16),
+
+    /// World versus World log.
+    WvW,
+
+    /// Map log.
+    Map,
+
+    /// Unknown encounter with the given species id.
+    Unknown(u16),
+}
+
+impl Encounter {
+    /// Boss id used for World versus World logs.
+    pub const WVW_ID: u16 = 1;
+
+    /// Boss id used for map logs.
+    pub const MAP_ID: u16 = 2;
+
+    /// Species ids of Special Forces Training Area golems.
+    pub const GOLEM_IDS: &'static [u16] = &[
+        16169, 16174, 16176, 16177, 16178, 16198, 16199, 16202, 19645, 19676,
+    ];
+
+    /// Identifies the encounter from a boss id as found in the [`Header`](crate::Header).
+    #[inline]
+    pub fn from_boss_id(boss_id: u16) -> Self {
+        match boss_id {
+            Self::WVW_ID => Self::WvW,
+            Self::MAP_ID => Self::Map,
+            species => Self::from_species(species),
+        }
+    }
+
+    /// Identifies the encounter from a species id.
+    pub fn from_species(species: u16) -> Self {
+        match species {
+            15438 => Self::ValeGuardian,
+            15429 => Self::Gorseval,
+            15375 => Self::Sabetha,
+            16123 => Self::Slothasor,
+            16088 | 16137 | 16125 => Self::BanditTrio,
+            16115 => Self::Matthias,
+            16253 => Self::Escort,
+            16235 => Self::KeepConstruct,
+            16247 => Self::TwistedCastle,
+            16246 | 16286 => Self::Xera,
+            17194 => Self::Cairn,
+            17172 => Self::MursaatOverseer,
+            17188 => Self::Samarog,
+            17154 => Self::Deimos,
+            19767 => Self::SoullessHorror,
+            19828 => Self::RiverOfSouls,
+            19691 => Self::BrokenKing,
+            19536 => Self::EaterOfSouls,
+            19651 | 19844 => Self::StatueOfDarkness,
+            19450 => Self::Dhuum,
+            43974 => Self::ConjuredAmalgamate,
+            21105 | 21089 => Self::TwinLargos,
+            20934 => Self::Qadim,
+            22006 => Self::Adina,
+            21964 => Self::Sabir,
+            22000 => Self::QadimThePeerless,
+            26725 => Self::Greer,
+            26774 => Self::Decima,
+            26712 => Self::Ura,
+            22154 => Self::IcebroodConstruct,
+            22343 | 22481 => Self::VoiceAndClaw,
+            22492 => Self::Fraenir,
+            22521 => Self::Boneskinner,
+            22711 => Self::WhisperOfJormag,
+            22836 => Self::ColdWar,
+            24033 => Self::AetherbladeHideout,
+            23957 => Self::XunlaiJadeJunkyard,
+            24485 => Self::KainengOverlook,
+            43488 => Self::HarvestTemple,
+            25413 | 25415 | 25419 => Self::OldLionsCourt,
+            25705 => Self::CosmicObservatory,
+            25989 => Self::TempleOfFebe,
+            17021 => Self::Mama,
+            17028 => Self::Siax,
+            16948 => Self::Ensolyss,
+            17632 => Self::Skorvald,
+            17949 => Self::Artsariiv,
+            17759 => Self::Arkk,
+            23254 => Self::Ai,
+            25577 => Self::Kanaxai,
+            species if Self::GOLEM_IDS.contains(&species) => Self::Golem(species),
+            species => Self::Unknown(species),
+        }
+    }
+
+    /// Returns the [`EncounterCategory`] of the encounter.
+    pub fn category(&self) -> EncounterCategory {
+        match self {
+            Self::ValeGuardian | Self::Gorseval | Self::Sabetha => EncounterCategory::Raid(1),
+            Self::Slothasor | Self::BanditTrio | Self::Matthias => EncounterCategory::Raid(2),
+            Self::Escort | Self::KeepConstruct | Self::TwistedCastle | Self::Xera => {
+                EncounterCategory::Raid(3)
+            }
+            Self::Cairn | Self::MursaatOverseer | Self::Samarog | Self::Deimos => {
+                EncounterCategory::Raid(4)
+            }
+            Self::SoullessHorror
+            | Self::RiverOfSouls
+            | Self::BrokenKing
+            | Self::EaterOfSouls
+            | Self::StatueOfDarkness
+            | Self::Dhuum => EncounterCategory::Raid(5),
+            Self::ConjuredAmalgamate | Self::TwinLargos | Self::Qadim => EncounterCategory::Raid(6),
+            Self::Adina | Self::Sabir | Self::QadimThePeerless => EncounterCategory::Raid(7),
+            Self::Greer | Self::Decima | Self::Ura => EncounterCategory::Raid(8),
+            Self::IcebroodConstruct
+            | Self::VoiceAndClaw
+            | Self::Fraenir
+            | Self::Boneskinner
+            | Self::WhisperOfJormag
+            | Self::ColdWar
+            | Self::AetherbladeHideout
+            | Self::XunlaiJadeJunkyard
+            | Self::KainengOverlook
+            | Self::HarvestTemple
+            | Self::OldLionsCourt
+            | Self::CosmicObservatory
+            | Self::TempleOfFebe => EncounterCategory::Strike,
+            Self::Mama
+            | Self::Siax
+            | Self::Ensolyss
+            | Self::Skorvald
+            | Self::Artsariiv
+            | Self::Arkk
+            | Self::Ai
+            | Self::Kanaxai => EncounterCategory::Fractal,
+            Self::Golem(_) => EncounterCategory::Golem,
+            Self::WvW => EncounterCategory::WvW,
+            Self::Map => EncounterCategory::Map,
+            Self::Unknown(_) => EncounterCategory::Unknown,
+        }
+    }
+
+    /// Returns the minimum fractal scale required for the challenge mode of a fractal encounter.
+    pub fn challenge_mode_scale(&self) -> Option<u64> {
+        match self {
+            Self::Mama | Self::Siax | Self::Ensolyss => Some(98),
+            Self::Skorvald | Self::Artsariiv | Self::Arkk | Self::Kanaxai => Some(99),
+            Self::Ai => Some(100),
+            _ => None,
+        }
+    }
+
+    /// Returns the maximum health threshold above which the target is in challenge mode.
+    ///
+    /// Only available for encounters where the challenge mode can be detected via health.
+    pub fn challenge_mode_health(&self) -> Option<u64> {
+        match self {
+            Self::MursaatOverseer => Some(25_000_000),
+            Self::Samarog => Some(30_000_000),
+            Self::Deimos => Some(40_000_000),
+            Self::Dhuum => Some(35_000_000),
+            Self::TwinLargos => Some(18_000_000),
+            Self::Qadim => Some(21_000_000),
+            Self::Adina => Some(23_000_000),
+            Self::Sabir => Some(32_000_000),
+            Self::QadimThePeerless => Some(48_000_000),
+            _ => None,
+        }
+    }
+}
+
+impl From<u16> for Encounter {
+    #[inline]
+    fn from(boss_id: u16) -> Self {
+        Self::from_boss_id(boss_id)
+    }
+}
+
+/// Category of an [`Encounter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EncounterCategory {
+    /// Raid encounter in the given wing.
+    Raid(u8),
+
+    /// Strike mission.
+    Strike,
+
+    /// Fractal encounter.
+    Fractal,
+
+    /// Special Forces Training Area golem.
+    Golem,
+
+    /// World versus World.
+    WvW,
+
+    /// Map log.
+    Map,
+
+    /// Unknown encounter.
+    Unknown,
+}
+
+impl Log {
+    /// Identifies the [`Encounter`] of the log.
+    ///
+    /// Uses the boss id in the [`Header`](crate::Header) and falls back to [`StateChange::LogNPCUpdate`] events for unknown ids.
+    pub fn encounter(&self) -> Encounter {
+        match self.header.encounter() {
+            Encounter::Unknown(_) => self
+                .events
+                .iter()
+                .filter(|event| event.get_statechange() == StateChange::LogNPCUpdate)
+                .map(|event| Encounter::from_species(event.src_agent as u16))
+                .find(|encounter| !matches!(encounter, Encounter::Unknown(_)))
+                .unwrap_or(self.header.encounter()),
+            encounter => encounter,
+        }
+    }
+
+    /// Checks whether the log was recorded in challenge mode.
+    ///
+    /// This is a heuristic based on [`StateChange::FractalScale`] for fractals
+    /// and [`StateChange::MaxHealthUpdate`] of the targets for other encounters.
+    /// Returns [`None`] if challenge mode can not be determined for the encounter.
+    pub fn is_challenge_mode(&self) -> Option<bool> {
+        let encounter = self.encounter();
+
+        if let Some(required) = encounter.challenge_mode_scale() {
+            let scale = self
+                .events
+                .iter()
+                .filter(|event| event.get_statechange() == StateChange::FractalScale)
+                .map(|event| event.src_agent)
+                .max()?;
+            return Some(scale >= required);
+        }
+
+        let threshold = encounter.challenge_mode_health()?;
+        let health = self
+            .events
+            .iter()
+            .filter(|event| event.get_statechange() == StateChange::MaxHealthUpdate)
+            .filter(|event| {
+                self.agent(event.src_agent).is_some_and(|agent| {
+                    matches!(agent.kind(), AgentKind::Npc(species) if Encounter::from_species(species) == encounter)
+                })
+            })
+            .map(|event| event.dst_agent)
+            .max()?;
+        Some(health > threshold)
+    }
+}
+
+impl LogTransformed {
+    /// Identifies the [`Encounter`] of the log.
+    ///
+    /// Uses the boss id in the [`Header`](crate::Header) and falls back to [`EventKind::LogNPCUpdate`] events for unknown ids.
+    pub fn encounter(&self) -> Encounter {
+        match self.header.encounter() {
+            Encounter::Unknown(_) => self
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    EventKind::LogNPCUpdate(event) => {
+                        Some(Encounter::from_species(event.id as u16))
+                    }
+                    _ => None,
+                })
+                .find(|encounter| !matches!(encounter, Encounter::Unknown(_)))
+                .unwrap_or(self.header.encounter()),
+            encounter => encounter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, Header, fixture};
+
+    fn log(boss_id: u16, events: Vec<Event>) -> Log {
+        fixture::log(boss_id, vec![fixture::npc(1, "Deimos", 17154)], events)
+    }
+
+    fn statechange(statechange: StateChange, src_agent: u64, dst_agent: u64) -> Event {
+        Event {
+            dst_agent,
+            ..fixture::statechange(0, src_agent, statechange)
+        }
+    }
+
+    #[test]
+    fn identify() {
+        assert_eq!(Encounter::from_boss_id(1), Encounter::WvW);
+        assert_eq!(Encounter::from_boss_id(16088), Encounter::BanditTrio);
+        assert_eq!(Encounter::from_boss_id(16199), Encounter::Golem(16199));
+        assert_eq!(Encounter::Xera.category(), EncounterCategory::Raid(3));
+
+        let log = log(
+            12345,
+            vec![statechange(StateChange::LogNPCUpdate, 17154, 1)],
+        );
+        assert_eq!(log.encounter(), Encounter::Deimos);
+    }
+
+    #[test]
+    fn challenge_mode() {
+        let log = log(
+            17154,
+            vec![statechange(StateChange::MaxHealthUpdate, 1, 42_000_000)],
+        );
+        assert_eq!(log.is_challenge_mode(), Some(true));
+
+        let mut log = log.clone();
+        log.events[0].dst_agent = 37_815_510;
+        assert_eq!(log.is_challenge_mode(), Some(false));
+
+        let log = Log {
+            header: Header {
+                boss_id: 17021,
+                ..log.header
+            },
+            events: vec![statechange(StateChange::FractalScale, 98, 0)],
+            ..log
+        };
+        assert_eq!(log.is_challenge_mode(), Some(true));
+    }
+}
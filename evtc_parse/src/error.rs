@@ -1,3 +1,4 @@
+use crate::Truncation;
 use std::{io, string};
 use thiserror::Error;
 
@@ -19,4 +20,30 @@ pub enum ParseError {
     /// Data is not in EVTC format.
     #[error("not in evtc format")]
     NotEvtc,
+
+    /// Compressed archive is invalid.
+    #[error("invalid archive: {0}")]
+    InvalidArchive(String),
+
+    /// Compressed archive contains no log.
+    #[error("archive is empty")]
+    EmptyArchive,
+
+    /// Compressed archive contains more than a single log.
+    #[error("archive contains {0} entries")]
+    MultipleEntries(usize),
+
+    /// Compression method of the archive is not supported.
+    #[error("unsupported compression method {0}")]
+    UnsupportedCompression(u16),
+
+    /// Log is truncated.
+    ///
+    /// Only returned in strict mode.
+    #[error("log truncated with {} dangling bytes", .0.dangling_bytes)]
+    Truncated(Truncation),
+
+    /// Event data at the given offset is not aligned.
+    #[error("event data at offset {0} not aligned")]
+    Unaligned(usize),
 }
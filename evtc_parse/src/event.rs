@@ -1,4 +1,7 @@
-use crate::{Event, Parse, Save, util::Endian};
+use crate::{
+    Event, Parse, Save,
+    util::{Endian, read_buffer},
+};
 use byteorder::{ReadBytesExt, WriteBytesExt};
 use std::io;
 
@@ -71,3 +74,132 @@ impl Save for Event {
         output.write_u8(self.pad64)
     }
 }
+
+/// Parses an [`Event`] from the input in the layout of EVTC revision 0.
+///
+/// Revision 0 uses smaller fields for overstack value and skill id and has no destination master instance id.
+/// The event is converted into the current layout.
+pub(crate) fn parse_revision0(input: &mut impl io::Read) -> Result<Event, io::Error> {
+    let time = input.read_u64::<Endian>()?;
+    let src_agent = input.read_u64::<Endian>()?;
+    let dst_agent = input.read_u64::<Endian>()?;
+    let value = input.read_i32::<Endian>()?;
+    let buff_dmg = input.read_i32::<Endian>()?;
+    let overstack_value = input.read_u16::<Endian>()?;
+    let skill_id = input.read_u16::<Endian>()?;
+    let src_instance_id = input.read_u16::<Endian>()?;
+    let dst_instance_id = input.read_u16::<Endian>()?;
+    let src_master_instance_id = input.read_u16::<Endian>()?;
+
+    // internal tracking bytes
+    read_buffer::<REVISION0_INTERNAL_SIZE>(input)?;
+
+    let event = Event {
+        time,
+        src_agent,
+        dst_agent,
+        value,
+        buff_dmg,
+        overstack_value: overstack_value.into(),
+        skill_id: skill_id.into(),
+        src_instance_id,
+        dst_instance_id,
+        src_master_instance_id,
+        dst_master_instance_id: 0,
+        affinity: input.read_u8()?,
+        buff: input.read_u8()?,
+        result: input.read_u8()?,
+        is_activation: input.read_u8()?,
+        is_buffremove: input.read_u8()?,
+        is_ninety: input.read_u8()?,
+        is_fifty: input.read_u8()?,
+        is_moving: input.read_u8()?,
+        is_statechange: input.read_u8()?,
+        is_flanking: input.read_u8()?,
+        is_shields: input.read_u8()?,
+        is_offcycle: input.read_u8()?,
+        pad61: 0,
+        pad62: 0,
+        pad63: 0,
+        pad64: 0,
+    };
+
+    // internal tracking byte
+    input.read_u8()?;
+
+    Ok(event)
+}
+
+/// Saves an [`Event`] to the output in the layout of EVTC revision 0.
+///
+/// Overstack value and skill id are truncated to 16 bits, destination master instance id and padding are discarded.
+pub(crate) fn save_revision0(event: &Event, output: &mut impl io::Write) -> Result<(), io::Error> {
+    output.write_u64::<Endian>(event.time)?;
+    output.write_u64::<Endian>(event.src_agent)?;
+    output.write_u64::<Endian>(event.dst_agent)?;
+    output.write_i32::<Endian>(event.value)?;
+    output.write_i32::<Endian>(event.buff_dmg)?;
+    output.write_u16::<Endian>(event.overstack_value as u16)?;
+    output.write_u16::<Endian>(event.skill_id as u16)?;
+    output.write_u16::<Endian>(event.src_instance_id)?;
+    output.write_u16::<Endian>(event.dst_instance_id)?;
+    output.write_u16::<Endian>(event.src_master_instance_id)?;
+
+    // internal tracking bytes
+    output.write_all(&[0; REVISION0_INTERNAL_SIZE])?;
+
+    output.write_u8(event.affinity)?;
+    output.write_u8(event.buff)?;
+    output.write_u8(event.result)?;
+    output.write_u8(event.is_activation)?;
+    output.write_u8(event.is_buffremove)?;
+    output.write_u8(event.is_ninety)?;
+    output.write_u8(event.is_fifty)?;
+    output.write_u8(event.is_moving)?;
+    output.write_u8(event.is_statechange)?;
+    output.write_u8(event.is_flanking)?;
+    output.write_u8(event.is_shields)?;
+    output.write_u8(event.is_offcycle)?;
+
+    // internal tracking byte
+    output.write_u8(0)
+}
+
+/// Size of internal tracking bytes in EVTC revision 0 events.
+const REVISION0_INTERNAL_SIZE: usize = 9;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StateChange, util::EVENT_SIZE};
+
+    #[test]
+    fn revision0() {
+        let event = Event {
+            time: 123,
+            src_agent: 1,
+            dst_agent: 2,
+            value: -100,
+            skill_id: 5492,
+            src_instance_id: 3,
+            dst_instance_id: 4,
+            src_master_instance_id: 5,
+            is_statechange: StateChange::EnterCombat.into(),
+            is_flanking: 1,
+            ..Event::default()
+        };
+
+        let mut data = Vec::new();
+        save_revision0(&event, &mut data).expect("failed to save event");
+        assert_eq!(data.len(), EVENT_SIZE);
+
+        let parsed = parse_revision0(&mut data.as_slice()).expect("failed to parse event");
+        assert_eq!(parsed.time, event.time);
+        assert_eq!(parsed.value, event.value);
+        assert_eq!(parsed.skill_id, event.skill_id);
+        assert_eq!(parsed.src_master_instance_id, 5);
+        assert_eq!(parsed.dst_master_instance_id, 0);
+        assert_eq!(parsed.get_statechange(), StateChange::EnterCombat);
+        assert_eq!(parsed.is_flanking, 1);
+    }
+}
@@ -0,0 +1,69 @@
+//! Shared fixtures for tests of this crate and its dependents.
+//!
+//! Not part of the public API.
+
+use crate::{Agent, Event, Header, Log, LogTransformed, StateChange};
+
+/// Creates a [`Header`] for the given boss id.
+pub fn header(boss_id: u16) -> Header {
+    Header {
+        date: "EVTC20230328".into(),
+        revision: 1,
+        boss_id,
+    }
+}
+
+/// Creates a [`Log`] without skills.
+pub fn log(boss_id: u16, agents: Vec<Agent>, events: Vec<Event>) -> Log {
+    Log {
+        header: header(boss_id),
+        agents,
+        skills: Vec::new(),
+        events,
+    }
+}
+
+/// Creates a [`LogTransformed`] without agents and skills.
+pub fn log_transformed<T>(events: Vec<T>) -> LogTransformed<T> {
+    LogTransformed {
+        header: header(123),
+        agents: Vec::new(),
+        skills: Vec::new(),
+        events,
+    }
+}
+
+/// Creates an [`Agent`] with the given names.
+pub fn agent(id: u64, name: &[&str]) -> Agent {
+    Agent {
+        id,
+        name: name.iter().map(|name| name.to_string()).collect(),
+        profession: 1,
+        is_elite: 0,
+        hitbox_width: 0,
+        hitbox_height: 0,
+        toughness: 0,
+        concentration: 0,
+        healing: 0,
+        condition: 0,
+    }
+}
+
+/// Creates an NPC [`Agent`] with the given species id.
+pub fn npc(id: u64, name: &str, species: u32) -> Agent {
+    Agent {
+        profession: species,
+        is_elite: u32::MAX,
+        ..agent(id, &[name])
+    }
+}
+
+/// Creates a state change [`Event`] with the given source agent.
+pub fn statechange(time: u64, src_agent: u64, statechange: StateChange) -> Event {
+    Event {
+        time,
+        src_agent,
+        is_statechange: statechange.into(),
+        ..Event::default()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    Parse, ParseError, Save,
+    Encounter, Parse, ParseError, Save,
     util::{Endian, read_string_buffer, write_string_buffer},
 };
 use byteorder::{ReadBytesExt, WriteBytesExt};
@@ -16,18 +16,36 @@ pub struct Header {
     pub date: String,
 
     /// EVTC API revision used.
+    ///
+    /// Logs in revision `0` are converted to the current event layout when parsing.
     pub revision: u8,
 
     /// Boss id of the log target.
     ///
     /// An id of `1` indicates a WvW log.
     /// An id of `2` indicates a map log.
+    /// See [`Encounter`](crate::Encounter) for identifying the encounter.
     pub boss_id: u16,
 }
 
 impl Header {
     /// Size of the date string.
     pub const DATE_SIZE: usize = 12;
+
+    /// Current EVTC API revision.
+    pub const REVISION: u8 = 1;
+
+    /// Identifies the [`Encounter`] from the boss id.
+    #[inline]
+    pub fn encounter(&self) -> Encounter {
+        Encounter::from_boss_id(self.boss_id)
+    }
+
+    /// Checks whether the log uses the legacy event layout of revision `0`.
+    #[inline]
+    pub fn is_revision0(&self) -> bool {
+        self.revision == 0
+    }
 }
 
 impl Parse for Header {
@@ -0,0 +1,610 @@
+use crate::{
+    Agent, Event, EventKind, Log, LogTransformed, LogTransformedLegacy, Skill, StateChange,
+    agent::AgentId,
+};
+use std::collections::{HashMap, hash_map::Entry};
+
+/// An index for fast lookups in a log.
+///
+/// Built once on demand via [`Log::index`] and borrows the [`Agent`]s and [`Skill`]s of the log.
+/// Lookups by id are constant time instead of a linear search.
+///
+/// ```no_run
+/// use evtc_parse::Log;
+///
+/// fn print_sources(log: &Log) {
+///     let index = log.index();
+///     for event in &log.events {
+///         if let Some(name) = index.agent_name(event.src_agent) {
+///             println!("{}: {:?}", event.time, name);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogIndex<'a> {
+    agents: HashMap<u64, &'a Agent>,
+    skills: HashMap<u32, &'a Skill>,
+    instances: HashMap<u16, Vec<InstanceRange>>,
+    masters: HashMap<u64, MasterRef>,
+}
+
+/// Reference to the master of an agent.
+#[derive(Debug, Clone, Copy)]
+struct MasterRef {
+    /// Instance id of the master.
+    instance_id: u16,
+
+    /// Time the master instance id was referenced.
+    time: u64,
+}
+
+impl<'a> LogIndex<'a> {
+    /// Creates a new index for the given [`Agent`]s and [`Skill`]s.
+    ///
+    /// The index contains no instance id information, see [`LogIndex::with_instances`].
+    pub fn new(agents: &'a [Agent], skills: &'a [Skill]) -> Self {
+        Self {
+            agents: agents.iter().map(|agent| (agent.id, agent)).collect(),
+            skills: skills.iter().map(|skill| (skill.id, skill)).collect(),
+            instances: HashMap::new(),
+            masters: HashMap::new(),
+        }
+    }
+
+    /// Adds instance id and master information from the given [`Event`]s to the index.
+    ///
+    /// The time range of an instance id is the time between the first and last event referencing it.
+    /// Ranges are split on [`StateChange::Spawn`], [`StateChange::Despawn`] and [`StateChange::IIDChange`],
+    /// since instance ids are reused after an agent despawned.
+    /// Only instance ids of [`Agent`]s present in the index are tracked.
+    pub fn with_instances<'e>(self, events: impl IntoIterator<Item = &'e Event>) -> Self {
+        self.track_instances(events.into_iter().map(InstanceEvent::from_event))
+    }
+
+    /// Adds instance id and master information from the given [`EventKind`]s to the index.
+    ///
+    /// See [`LogIndex::with_instances`] for details.
+    pub fn with_transformed_instances<'e>(
+        self,
+        events: impl IntoIterator<Item = &'e EventKind>,
+    ) -> Self {
+        self.track_instances(events.into_iter().map(InstanceEvent::from_kind))
+    }
+
+    /// Tracks instance ids and masters from the given events.
+    fn track_instances(mut self, events: impl Iterator<Item = InstanceEvent>) -> Self {
+        let mut lifetimes = Lifetimes::default();
+        let mut masters = HashMap::new();
+        let mut track_master = |agent: &AgentId, time: u64| {
+            if agent.has_master() {
+                masters.entry(agent.id).or_insert(MasterRef {
+                    instance_id: agent.master_instance_id,
+                    time,
+                });
+            }
+        };
+
+        let track = |lifetimes: &mut Lifetimes, agent: &AgentId, time: u64| {
+            if agent.instance_id != 0 && self.agents.contains_key(&agent.id) {
+                lifetimes.track(agent.instance_id, agent.id, time);
+            }
+        };
+
+        for event in events {
+            match event {
+                InstanceEvent::Combat { time, src, dst } => {
+                    track(&mut lifetimes, &src, time);
+                    track_master(&src, time);
+                    track(&mut lifetimes, &dst, time);
+                    track_master(&dst, time);
+                }
+                InstanceEvent::Spawn { time, agent } => {
+                    lifetimes.close(agent.id, time);
+                    track(&mut lifetimes, &agent, time);
+                }
+                InstanceEvent::Despawn { time, agent } => {
+                    track(&mut lifetimes, &agent, time);
+                    lifetimes.close(agent.id, time);
+                }
+                InstanceEvent::IIDChange { time, previous, id } => {
+                    // new agent continues with the instance id of the previous agent
+                    if let Some(instance_id) = lifetimes.close(previous, time) {
+                        track(&mut lifetimes, &AgentId::new(id, instance_id, 0), time);
+                    }
+                }
+                InstanceEvent::Agent { time, agent } => {
+                    track(&mut lifetimes, &agent, time);
+                    track_master(&agent, time);
+                }
+                InstanceEvent::None => {}
+            }
+        }
+        self.masters = masters;
+
+        for (instance_id, range) in lifetimes.finish() {
+            self.instances.entry(instance_id).or_default().push(range);
+        }
+        for ranges in self.instances.values_mut() {
+            ranges.sort_by_key(|range| (range.first, range.last));
+        }
+
+        self
+    }
+
+    /// Returns the [`Agent`] with the given id.
+    #[inline]
+    pub fn agent(&self, id: u64) -> Option<&'a Agent> {
+        self.agents.get(&id).copied()
+    }
+
+    /// Returns the name(s) of the [`Agent`] with the given id.
+    #[inline]
+    pub fn agent_name(&self, id: u64) -> Option<&'a [String]> {
+        self.agent(id).map(|agent| agent.name.as_slice())
+    }
+
+    /// Returns the [`Skill`] with the given id.
+    #[inline]
+    pub fn skill(&self, id: u32) -> Option<&'a Skill> {
+        self.skills.get(&id).copied()
+    }
+
+    /// Returns the name of the [`Skill`] with the given id.
+    #[inline]
+    pub fn skill_name(&self, id: u32) -> Option<&'a str> {
+        self.skill(id).map(|skill| skill.name.as_str())
+    }
+
+    /// Returns the time ranges the given instance id was in use, sorted by time.
+    #[inline]
+    pub fn instance_ranges(&self, instance_id: u16) -> &[InstanceRange] {
+        self.instances
+            .get(&instance_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the id of the agent using the given instance id at the given time.
+    ///
+    /// If multiple agents overlap, the agent using the instance id most recently is returned.
+    #[inline]
+    pub fn agent_at(&self, instance_id: u16, time: u64) -> Option<u64> {
+        self.instance_ranges(instance_id)
+            .iter()
+            .rev()
+            .find(|range| range.contains(time))
+            .map(|range| range.agent)
+    }
+
+    /// Returns the [`Agent`] using the given instance id at the given time.
+    #[inline]
+    pub fn agent_by_instance(&self, instance_id: u16, time: u64) -> Option<&'a Agent> {
+        self.agent_at(instance_id, time)
+            .and_then(|id| self.agent(id))
+    }
+
+    /// Returns the [`Agent`] using the given instance id closest to the given time.
+    ///
+    /// Prefers an agent using the instance id at the given time, otherwise the agent with the nearest time range.
+    pub fn agent_by_instance_closest(&self, instance_id: u16, time: u64) -> Option<&'a Agent> {
+        self.instance_ranges(instance_id)
+            .iter()
+            .min_by_key(|range| range.distance(time))
+            .and_then(|range| self.agent(range.agent))
+    }
+
+    /// Returns the direct master of the [`Agent`] with the given id.
+    ///
+    /// For example the player owning a minion or the minion spawning another minion.
+    pub fn master(&self, id: u64) -> Option<&'a Agent> {
+        let master = self.masters.get(&id)?;
+        self.agent_by_instance_closest(master.instance_id, master.time)
+            .filter(|agent| agent.id != id)
+    }
+
+    /// Returns the master of the given [`AgentId`] at the given time.
+    ///
+    /// Uses the master instance id if present, otherwise falls back to [`LogIndex::master`].
+    pub fn master_at(&self, agent: &AgentId, time: u64) -> Option<&'a Agent> {
+        if agent.has_master() {
+            self.agent_by_instance_closest(agent.master_instance_id, time)
+                .filter(|master| master.id != agent.id)
+        } else {
+            self.master(agent.id)
+        }
+    }
+
+    /// Returns the top-most master owning the [`Agent`] with the given id.
+    ///
+    /// Follows chains of masters, for example a minion spawned by another minion.
+    /// Returns the agent itself if it has no master.
+    pub fn owner(&self, id: u64) -> Option<&'a Agent> {
+        self.follow_masters(self.agent(id)?)
+    }
+
+    /// Returns the top-most master owning the given [`AgentId`] at the given time.
+    ///
+    /// Returns the agent itself if it has no master.
+    pub fn owner_at(&self, agent: &AgentId, time: u64) -> Option<&'a Agent> {
+        match self.master_at(agent, time) {
+            Some(master) => self.follow_masters(master),
+            None => self.agent(agent.id),
+        }
+    }
+
+    /// Returns the top-most master owning the source of the [`Event`].
+    #[inline]
+    pub fn src_owner(&self, event: &Event) -> Option<&'a Agent> {
+        self.owner_at(&AgentId::from_src(event), event.time)
+    }
+
+    /// Returns the top-most master owning the destination of the [`Event`].
+    #[inline]
+    pub fn dst_owner(&self, event: &Event) -> Option<&'a Agent> {
+        self.owner_at(&AgentId::from_dst(event), event.time)
+    }
+
+    /// Follows the chain of masters starting from the given [`Agent`].
+    fn follow_masters(&self, mut agent: &'a Agent) -> Option<&'a Agent> {
+        // limit depth to guard against cycles
+        for _ in 0..self.agents.len() {
+            match self.master(agent.id) {
+                Some(master) => agent = master,
+                None => break,
+            }
+        }
+        Some(agent)
+    }
+}
+
+/// Event relevant for instance id tracking.
+#[derive(Debug, Clone)]
+enum InstanceEvent {
+    /// Combat event with source and destination agents.
+    Combat {
+        time: u64,
+        src: AgentId,
+        dst: AgentId,
+    },
+
+    /// Agent spawned.
+    Spawn { time: u64, agent: AgentId },
+
+    /// Agent despawned.
+    Despawn { time: u64, agent: AgentId },
+
+    /// Agent id changed.
+    IIDChange { time: u64, previous: u64, id: u64 },
+
+    /// Other event referencing an agent.
+    Agent { time: u64, agent: AgentId },
+
+    /// Event not referencing an agent.
+    None,
+}
+
+impl InstanceEvent {
+    /// Creates the instance event for an [`Event`].
+    fn from_event(event: &Event) -> Self {
+        let time = event.time;
+        let agent = AgentId::from_src(event);
+        match event.get_statechange() {
+            // destination only refers to an agent for combat events
+            StateChange::Combat => Self::Combat {
+                time,
+                src: agent,
+                dst: AgentId::from_dst(event),
+            },
+            StateChange::Spawn => Self::Spawn { time, agent },
+            StateChange::Despawn => Self::Despawn { time, agent },
+            // source contains the old id, destination the new id
+            StateChange::IIDChange => Self::IIDChange {
+                time,
+                previous: agent.id,
+                id: event.dst_agent,
+            },
+            _ => Self::Agent { time, agent },
+        }
+    }
+
+    /// Creates the instance event for an [`EventKind`].
+    fn from_kind(event: &EventKind) -> Self {
+        let agent = |time: u64, agent: &AgentId| Self::Agent {
+            time,
+            agent: agent.clone(),
+        };
+        match event {
+            EventKind::Combat(event) => Self::Combat {
+                time: event.common.time,
+                src: event.common.source.clone(),
+                dst: event.common.target.clone(),
+            },
+            EventKind::Spawn(event) => Self::Spawn {
+                time: event.time,
+                agent: event.agent.clone(),
+            },
+            EventKind::Despawn(event) => Self::Despawn {
+                time: event.time,
+                agent: event.agent.clone(),
+            },
+            EventKind::IIDChange { time, id, previous } => Self::IIDChange {
+                time: *time,
+                previous: *previous,
+                id: *id,
+            },
+            EventKind::ApiDelayed { event } => Self::from_kind(event),
+            EventKind::EnterCombat(event) => agent(event.time, &event.agent),
+            EventKind::ExitCombat(event)
+            | EventKind::ChangeUp(event)
+            | EventKind::ChangeDead(event)
+            | EventKind::ChangeDown(event)
+            | EventKind::PointOfView(event) => agent(event.time, &event.agent),
+            EventKind::HealthUpdate(event) => agent(event.time, &event.agent),
+            EventKind::MaxHealthUpdate(event) => agent(event.time, &event.agent),
+            EventKind::BarrierUpdate(event) => agent(event.time, &event.agent),
+            EventKind::WeaponSwap(event) => agent(event.time, &event.agent),
+            EventKind::Position(event) | EventKind::Velocity(event) | EventKind::Facing(event) => {
+                agent(event.time, &event.agent)
+            }
+            EventKind::BreakbarState(event) => agent(event.time, &event.agent),
+            EventKind::BreakbarPercent(event) => agent(event.time, &event.agent),
+            EventKind::AnimationStart(event) => agent(event.time, &event.agent),
+            EventKind::AnimationStop(event) => agent(event.time, &event.agent),
+            _ => Self::None,
+        }
+    }
+}
+
+/// Builder for time ranges of instance ids.
+#[derive(Debug, Default)]
+struct Lifetimes {
+    /// Currently open ranges by agent id.
+    open: HashMap<u64, (u16, InstanceRange)>,
+
+    /// Closed ranges.
+    closed: Vec<(u16, InstanceRange)>,
+}
+
+impl Lifetimes {
+    /// Tracks an instance id used by an agent at the given time.
+    fn track(&mut self, instance_id: u16, agent: u64, time: u64) {
+        let new = InstanceRange {
+            agent,
+            first: time,
+            last: time,
+        };
+        match self.open.entry(agent) {
+            Entry::Occupied(mut entry) => {
+                let (id, range) = entry.get_mut();
+                if *id == instance_id {
+                    range.first = range.first.min(time);
+                    range.last = range.last.max(time);
+                } else {
+                    let old = entry.insert((instance_id, new));
+                    self.closed.push(old);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((instance_id, new));
+            }
+        }
+    }
+
+    /// Closes the open range of an agent at the given time.
+    ///
+    /// Returns the instance id of the closed range.
+    fn close(&mut self, agent: u64, time: u64) -> Option<u16> {
+        let (instance_id, mut range) = self.open.remove(&agent)?;
+        range.last = range.last.max(time);
+        self.closed.push((instance_id, range));
+        Some(instance_id)
+    }
+
+    /// Finishes all ranges.
+    fn finish(self) -> impl Iterator<Item = (u16, InstanceRange)> {
+        self.closed.into_iter().chain(self.open.into_values())
+    }
+}
+
+/// Time range an instance id was used by an [`Agent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InstanceRange {
+    /// Id of the [`Agent`] using the instance id.
+    pub agent: u64,
+
+    /// Time of the first event referencing the instance id.
+    pub first: u64,
+
+    /// Time of the last event referencing the instance id.
+    pub last: u64,
+}
+
+impl InstanceRange {
+    /// Checks whether the given time is within the range.
+    #[inline]
+    pub fn contains(&self, time: u64) -> bool {
+        (self.first..=self.last).contains(&time)
+    }
+
+    /// Returns the distance of the given time to the range.
+    #[inline]
+    pub fn distance(&self, time: u64) -> u64 {
+        self.first.saturating_sub(time) + time.saturating_sub(self.last)
+    }
+}
+
+impl Log {
+    /// Builds a [`LogIndex`] for fast lookups of agents, skills and instance ids.
+    ///
+    /// The index also resolves minions to their masters, see [`LogIndex::owner`].
+    #[inline]
+    pub fn index(&self) -> LogIndex<'_> {
+        LogIndex::new(&self.agents, &self.skills).with_instances(&self.events)
+    }
+}
+
+impl LogTransformed {
+    /// Builds a [`LogIndex`] for fast lookups of agents, skills and instance ids.
+    ///
+    /// The index also resolves minions to their masters, see [`LogIndex::owner`].
+    #[inline]
+    pub fn index(&self) -> LogIndex<'_> {
+        LogIndex::new(&self.agents, &self.skills).with_transformed_instances(&self.events)
+    }
+}
+
+impl LogTransformedLegacy {
+    /// Builds a [`LogIndex`] for fast lookups of agents and skills.
+    ///
+    /// Legacy events are not tracked, so the index contains no instance id information.
+    #[inline]
+    pub fn index(&self) -> LogIndex<'_> {
+        LogIndex::new(&self.agents, &self.skills)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture;
+
+    fn agent(id: u64) -> Agent {
+        fixture::agent(id, &[&format!("Agent {id}")])
+    }
+
+    fn event(time: u64, src_agent: u64, src_instance_id: u16) -> Event {
+        Event {
+            src_instance_id,
+            ..fixture::statechange(time, src_agent, StateChange::Combat)
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        let log = Log {
+            header: fixture::header(123),
+            agents: vec![agent(1), agent(2)],
+            skills: vec![Skill {
+                id: 7,
+                name: "Skill Name".into(),
+            }],
+            events: vec![
+                event(10, 1, 5),
+                event(20, 1, 5),
+                event(30, 2, 5),
+                Event {
+                    dst_agent: 2,
+                    dst_instance_id: 5,
+                    ..event(40, 1, 6)
+                },
+                event(50, 3, 5),
+            ],
+        };
+        let index = log.index();
+
+        assert_eq!(index.agent(2), Some(&log.agents[1]));
+        assert_eq!(index.agent_name(3), None);
+        assert_eq!(index.skill_name(7), Some("Skill Name"));
+        assert_eq!(
+            index.instance_ranges(5),
+            [
+                InstanceRange {
+                    agent: 1,
+                    first: 10,
+                    last: 20,
+                },
+                InstanceRange {
+                    agent: 2,
+                    first: 30,
+                    last: 40,
+                },
+            ]
+        );
+        assert_eq!(index.agent_by_instance(5, 15), Some(&log.agents[0]));
+        assert_eq!(index.agent_by_instance(5, 35), Some(&log.agents[1]));
+        assert_eq!(index.agent_by_instance(5, 25), None);
+        assert_eq!(index.agent_by_instance(6, 40), Some(&log.agents[0]));
+    }
+
+    #[test]
+    fn lifetimes() {
+        let statechange = |time, src_agent, statechange: StateChange| Event {
+            is_statechange: statechange.into(),
+            ..event(time, src_agent, 5)
+        };
+        let log = fixture::log(
+            123,
+            vec![agent(1), agent(2), agent(3)],
+            vec![
+                statechange(10, 1, StateChange::Spawn),
+                event(20, 1, 5),
+                statechange(30, 1, StateChange::Despawn),
+                statechange(40, 2, StateChange::Spawn),
+                event(50, 2, 5),
+                Event {
+                    dst_agent: 3,
+                    ..statechange(60, 2, StateChange::IIDChange)
+                },
+                event(70, 3, 5),
+                statechange(80, 1, StateChange::Spawn),
+                event(90, 1, 5),
+            ],
+        );
+        let index = log.index();
+
+        assert_eq!(index.agent_at(5, 20), Some(1));
+        assert_eq!(index.agent_at(5, 35), None);
+        assert_eq!(index.agent_at(5, 50), Some(2));
+        assert_eq!(index.agent_at(5, 65), Some(3));
+        assert_eq!(index.agent_at(5, 70), Some(3));
+        assert_eq!(index.agent_at(5, 75), None);
+        assert_eq!(index.agent_at(5, 85), Some(1));
+        assert_eq!(index.instance_ranges(5).len(), 4);
+
+        let log = log.into_transformed();
+        let index = log.index();
+        assert_eq!(index.agent_at(5, 50), Some(2));
+        assert_eq!(index.agent_at(5, 65), Some(3));
+        assert_eq!(index.agent_at(5, 75), None);
+        assert_eq!(index.agent_at(5, 85), Some(1));
+    }
+
+    #[test]
+    fn masters() {
+        let log = fixture::log(
+            123,
+            vec![agent(1), agent(2), agent(3), agent(4)],
+            vec![
+                event(10, 1, 5),
+                Event {
+                    src_master_instance_id: 5,
+                    ..event(20, 2, 6)
+                },
+                Event {
+                    src_master_instance_id: 6,
+                    ..event(30, 3, 7)
+                },
+                event(40, 1, 5),
+                event(50, 4, 8),
+            ],
+        );
+        let index = log.index();
+
+        assert_eq!(index.master(2).map(|agent| agent.id), Some(1));
+        assert_eq!(index.master(3).map(|agent| agent.id), Some(2));
+        assert_eq!(index.master(1), None);
+        assert_eq!(index.owner(3).map(|agent| agent.id), Some(1));
+        assert_eq!(index.owner(4).map(|agent| agent.id), Some(4));
+        assert_eq!(
+            index.src_owner(&log.events[2]).map(|agent| agent.id),
+            Some(1)
+        );
+        assert_eq!(
+            index
+                .owner_at(&AgentId::new(4, 8, 5), 50)
+                .map(|agent| agent.id),
+            Some(1)
+        );
+    }
+}
@@ -19,8 +19,23 @@
 //! }
 //! ```
 //!
+//! For large logs a [`LogReader`] allows to stream events without holding the whole [`Log`] in memory.
+//! ```no_run
+//! use evtc_parse::LogReader;
+//! use std::io;
+//!
+//! fn count_events(input: impl io::Read) -> usize {
+//!     let reader = LogReader::new(input).expect("failed to parse");
+//!     reader.filter_map(Result::ok).count()
+//! }
+//! ```
+//!
+//! A [`LogRef`] borrows events directly from a buffer without copying, for example from a memory-mapped file.
+//!
 //! Note that ArcDPS can save compressed log files with `.zevtc` as file extension.
 //! Enabling the `"zevtc"` or `"zip"` feature adds support for compressed logs.
+//!
+//! The `"serde"` and `"strum"` features enable the respective derives of the re-exported [`evtc`] types.
 
 /// Extensions for log EVTC API.
 #[path = "."]
@@ -29,18 +44,33 @@ mod ext {
     pub mod event;
     pub mod skill;
 }
+pub mod analysis;
+mod encounter;
 mod error;
+#[doc(hidden)]
+pub mod fixture;
 mod header;
+mod index;
 mod log;
+#[cfg(target_endian = "little")]
+mod log_ref;
 mod log_transformed;
+mod options;
+mod reader;
 mod util;
 
+pub use self::encounter::*;
 pub use self::error::*;
 pub use self::ext::agent::*;
 pub use self::ext::skill::*;
 pub use self::header::*;
+pub use self::index::*;
 pub use self::log::*;
+#[cfg(target_endian = "little")]
+pub use self::log_ref::*;
 pub use self::log_transformed::*;
+pub use self::options::*;
+pub use self::reader::*;
 pub use evtc::*;
 
 #[cfg(feature = "zevtc")]
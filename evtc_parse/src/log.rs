@@ -1,10 +1,14 @@
 use crate::{
-    Agent, Header, LogTransformed, LogTransformedLegacy, Parse, ParseError, Save, Skill,
-    util::Endian,
+    Agent, Header, LogReader, LogTransformed, LogTransformedLegacy, Parse, ParseError,
+    ParseOptions, Save, Skill, ext::event::save_revision0, util::Endian,
 };
-use byteorder::{ReadBytesExt, WriteBytesExt};
+use byteorder::WriteBytesExt;
 use evtc::Event;
-use std::{fs::File, io, path::Path};
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -33,18 +37,58 @@ impl Log {
     ///
     /// With the `"zevtc"` or `"zip"` feature enabled this also supports compressed log files.
     pub fn parse_file(path: impl AsRef<Path>) -> Result<Log, ParseError> {
+        Self::parse_file_with(path, ParseOptions::default())
+    }
+
+    /// Parses a [`Log`] from a given [`Path`] to a log file with the given [`ParseOptions`].
+    ///
+    /// With the `"zevtc"` or `"zip"` feature enabled this also supports compressed log files.
+    pub fn parse_file_with(
+        path: impl AsRef<Path>,
+        options: ParseOptions,
+    ) -> Result<Log, ParseError> {
         let path = path.as_ref();
         let mut file = io::BufReader::new(File::open(path)?);
 
         #[cfg(feature = "zevtc")]
         if let Some("zevtc" | "zip") = path.extension().and_then(|ext| ext.to_str()) {
-            return Self::parse_zevtc(file);
+            return Self::parse_zevtc_with(file, options);
         }
 
-        Log::parse(&mut file)
+        Log::parse_with(&mut file, options)
+    }
+
+    /// Parses a [`Log`] from the input with the given [`ParseOptions`].
+    ///
+    /// Use [`LogReader::into_log_with_truncation`] to find out whether the log was truncated.
+    pub fn parse_with(input: impl io::Read, options: ParseOptions) -> Result<Log, ParseError> {
+        LogReader::with_options(input, options)?.into_log()
+    }
+
+    /// Saves the [`Log`] to a file at the given [`Path`].
+    ///
+    /// With the `"zevtc"` or `"zip"` feature enabled this saves a compressed log file for a `.zevtc` or `.zip` file extension.
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        let path = path.as_ref();
+        let mut file = io::BufWriter::new(File::create(path)?);
+
+        #[cfg(feature = "zevtc")]
+        if let Some("zevtc" | "zip") = path.extension().and_then(|ext| ext.to_str()) {
+            let entry = path.with_extension("evtc");
+            let name = entry
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(Self::ZEVTC_ENTRY_NAME);
+            return self.save_zevtc_named(file, name);
+        }
+
+        self.save(&mut file)?;
+        file.flush()
     }
 
     /// Returns the [`Agent`] with the given id.
+    ///
+    /// This performs a linear search, use [`Log::index`] for repeated lookups.
     #[inline]
     pub fn agent(&self, id: u64) -> Option<&Agent> {
         self.agents.iter().find(|agent| agent.id == id)
@@ -63,6 +107,8 @@ impl Log {
     }
 
     /// Returns the [`Skill`] with the given id.
+    ///
+    /// This performs a linear search, use [`Log::index`] for repeated lookups.
     #[inline]
     pub fn skill(&self, id: u32) -> Option<&Skill> {
         self.skills.iter().find(|skill| skill.id == id)
@@ -97,33 +143,11 @@ impl Parse for Log {
     type Error = ParseError;
 
     fn parse(input: &mut impl io::Read) -> Result<Self, Self::Error> {
-        let header = Header::parse(input)?;
-
-        // we only support current revision
-        if header.revision != 1 {
-            return Err(ParseError::UnsupportedRevision(header.revision));
-        }
-
-        let agent_count = input.read_u32::<Endian>()?;
-        let agents = Agent::parse_multi(input, agent_count as usize)?;
-
-        let skill_count = input.read_u32::<Endian>()?;
-        let skills = Skill::parse_multi(input, skill_count as usize)?;
-
-        let mut events = Vec::new();
-        while let Ok(event) = Event::parse(input) {
-            events.push(event);
-        }
-
-        Ok(Self {
-            header,
-            agents,
-            skills,
-            events,
-        })
+        Self::parse_with(input, ParseOptions::default())
     }
 }
 
+/// Events are saved in the layout of the revision given in the [`Header`].
 impl Save for Log {
     type Error = io::Error;
 
@@ -140,10 +164,32 @@ impl Save for Log {
             skill.save(output)?;
         }
 
-        for event in &self.events {
-            event.save(output)?;
+        if self.header.is_revision0() {
+            for event in &self.events {
+                save_revision0(event, output)?;
+            }
+        } else {
+            for event in &self.events {
+                event.save(output)?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// Information about a truncated log.
+///
+/// This happens for example when ArcDPS crashes while writing the log.
+/// Reported by [`LogReader::truncation`] and [`LogRef::parse_with_truncation`](crate::LogRef::parse_with_truncation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Truncation {
+    /// Amount of dangling bytes after the last complete event.
+    pub dangling_bytes: usize,
+
+    /// Index of the last event returned before the truncation, if any.
+    ///
+    /// Events discarded by [`ParseOptions::statechanges`] are not counted.
+    pub last_event: Option<usize>,
+}
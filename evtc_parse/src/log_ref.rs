@@ -0,0 +1,294 @@
+use crate::{
+    Agent, Header, Log, LogIndex, Parse, ParseError, ParseOptions, Skill, Truncation,
+    util::{EVENT_SIZE, Endian, skip_bytes},
+};
+use byteorder::ReadBytesExt;
+use evtc::Event;
+use std::{
+    borrow::Cow,
+    mem::{align_of, size_of},
+    slice,
+};
+
+/// A borrowed view of an EVTC log.
+///
+/// The [`Header`], [`Agent`]s and [`Skill`]s are parsed into owned values.
+/// The [`Event`]s are borrowed directly from the input buffer without copying,
+/// since the in-memory layout of [`Event`] is identical to the EVTC file format.
+///
+/// The input buffer needs to be in the current EVTC revision and the events need to be aligned for [`Event`].
+/// Memory-mapped files or buffers allocated with sufficient alignment can be used as input.
+///
+/// Events start at byte offset `24 + 96 * agents + 68 * skills` into the buffer.
+/// With an odd amount of skills the events are misaligned even in an aligned buffer,
+/// which [`LogRef::parse`] reports as [`ParseError::Unaligned`].
+/// [`LogRef::parse_or_copy`] instead falls back to copying the events.
+///
+/// Only available on little endian targets.
+///
+/// ```no_run
+/// use evtc_parse::LogRef;
+///
+/// fn count_events(data: &[u8]) -> usize {
+///     let log = LogRef::parse(data).expect("failed to parse");
+///     log.events.len()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogRef<'a> {
+    /// The log header with meta information.
+    pub header: Header,
+
+    /// Agents (entities) present in the log.
+    pub agents: Vec<Agent>,
+
+    /// Information about skills used in the log.
+    pub skills: Vec<Skill>,
+
+    /// Every [`Event`] occurring in the log, borrowed from the input buffer.
+    ///
+    /// Only owned if copied by [`LogRef::parse_or_copy`].
+    /// Some events may also hold meta information, for example [`StateChange::BuffFormula`](crate::StateChange::BuffFormula).
+    pub events: Cow<'a, [Event]>,
+}
+
+// ensure event layout matches file format
+const _: () = assert!(size_of::<Event>() == LogRef::EVENT_SIZE);
+
+impl<'a> LogRef<'a> {
+    /// Size of an event in the EVTC file format.
+    pub const EVENT_SIZE: usize = EVENT_SIZE;
+
+    /// Parses a [`LogRef`] from a buffer containing an EVTC log.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse_with(data, ParseOptions::default())
+    }
+
+    /// Parses a [`LogRef`] from a buffer containing an EVTC log with the given [`ParseOptions`].
+    ///
+    /// Events are always borrowed as a whole, only [`ParseOptions::strict`], [`ParseOptions::skip_skills`] and [`ParseOptions::string_decoding`] are taken into account.
+    #[inline]
+    pub fn parse_with(data: &'a [u8], options: ParseOptions) -> Result<Self, ParseError> {
+        Self::parse_with_truncation(data, options).map(|(log, _)| log)
+    }
+
+    /// Parses a [`LogRef`] from a buffer containing an EVTC log with the given [`ParseOptions`],
+    /// returning the [`Truncation`] if the log ended with an incomplete event.
+    pub fn parse_with_truncation(
+        data: &'a [u8],
+        options: ParseOptions,
+    ) -> Result<(Self, Option<Truncation>), ParseError> {
+        Self::parse_inner(data, options, false)
+    }
+
+    /// Parses a [`LogRef`] from a buffer containing an EVTC log, copying the events if they are not aligned.
+    pub fn parse_or_copy(data: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse_or_copy_with(data, ParseOptions::default())
+    }
+
+    /// Parses a [`LogRef`] from a buffer containing an EVTC log with the given [`ParseOptions`],
+    /// copying the events if they are not aligned.
+    ///
+    /// See [`LogRef::parse_with`] for the supported options.
+    #[inline]
+    pub fn parse_or_copy_with(data: &'a [u8], options: ParseOptions) -> Result<Self, ParseError> {
+        Self::parse_inner(data, options, true).map(|(log, _)| log)
+    }
+
+    /// Parses the log, optionally copying unaligned events.
+    fn parse_inner(
+        data: &'a [u8],
+        options: ParseOptions,
+        copy_unaligned: bool,
+    ) -> Result<(Self, Option<Truncation>), ParseError> {
+        let mut input = data;
+
+        let header = Header::parse(&mut input)?;
+
+        // events can only be borrowed in current revision
+        if header.revision != Header::REVISION {
+            return Err(ParseError::UnsupportedRevision(header.revision));
+        }
+
+        let agent_count = input.read_u32::<Endian>()?;
+        let agents = (0..agent_count)
+            .map(|_| Agent::parse_with(&mut input, options.string_decoding))
+            .collect::<Result<_, _>>()?;
+
+        let skill_count = input.read_u32::<Endian>()?;
+        let skills = if !options.skip_skills {
+            (0..skill_count)
+                .map(|_| Skill::parse_with(&mut input, options.string_decoding))
+                .collect::<Result<_, _>>()?
+        } else {
+            skip_bytes(&mut input, skill_count as u64 * Skill::SIZE as u64)?;
+            Vec::new()
+        };
+
+        let offset = data.len() - input.len();
+        let dangling_bytes = input.len() % Self::EVENT_SIZE;
+        let (input, _) = input.split_at(input.len() - dangling_bytes);
+        let events = Self::events_from_bytes(input, offset, copy_unaligned)?;
+
+        let truncation = (dangling_bytes > 0).then(|| Truncation {
+            dangling_bytes,
+            last_event: events.len().checked_sub(1),
+        });
+        if let Some(truncation) = truncation.filter(|_| options.strict) {
+            return Err(ParseError::Truncated(truncation));
+        }
+
+        let log = Self {
+            header,
+            agents,
+            skills,
+            events,
+        };
+        Ok((log, truncation))
+    }
+
+    /// Reinterprets the bytes at the given offset as events.
+    ///
+    /// Unaligned events are copied if enabled.
+    fn events_from_bytes(
+        bytes: &'a [u8],
+        offset: usize,
+        copy_unaligned: bool,
+    ) -> Result<Cow<'a, [Event]>, ParseError> {
+        let ptr = bytes.as_ptr();
+        if ptr.align_offset(align_of::<Event>()) != 0 {
+            if !copy_unaligned {
+                return Err(ParseError::Unaligned(offset));
+            }
+            let events = bytes
+                .chunks_exact(Self::EVENT_SIZE)
+                .map(|mut chunk| Event::parse(&mut chunk))
+                .collect::<Result<_, _>>()?;
+            return Ok(Cow::Owned(events));
+        }
+
+        // event only consists of integers, every bit pattern is valid
+        // length is a multiple of event size and alignment has been validated above
+        let events = unsafe { slice::from_raw_parts(ptr.cast(), bytes.len() / Self::EVENT_SIZE) };
+        Ok(Cow::Borrowed(events))
+    }
+
+    /// Builds a [`LogIndex`] for fast lookups of agents, skills and instance ids.
+    #[inline]
+    pub fn index(&self) -> LogIndex<'_> {
+        LogIndex::new(&self.agents, &self.skills).with_instances(self.events.iter())
+    }
+
+    /// Converts the borrowed log into an owned [`Log`].
+    #[inline]
+    pub fn to_log(&self) -> Log {
+        Log {
+            header: self.header.clone(),
+            agents: self.agents.clone(),
+            skills: self.skills.clone(),
+            events: self.events.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Save, StateChange, fixture};
+
+    fn log(skill_count: u32) -> Log {
+        let events = (0..3)
+            .map(|time| fixture::statechange(time, 0, StateChange::Position))
+            .collect();
+        Log {
+            skills: (0..skill_count)
+                .map(|id| Skill {
+                    id,
+                    name: "Skill Name".into(),
+                })
+                .collect(),
+            ..fixture::log(123, Vec::new(), events)
+        }
+    }
+
+    /// Saves the log into a buffer aligned for events.
+    fn save_aligned(log: &Log, buffer: &mut Vec<u64>) -> usize {
+        let mut data = Vec::new();
+        log.save(&mut data).expect("failed to save log");
+
+        buffer.resize(data.len().div_ceil(size_of::<u64>()), 0);
+        let bytes =
+            unsafe { slice::from_raw_parts_mut(buffer.as_mut_ptr().cast::<u8>(), data.len()) };
+        bytes.copy_from_slice(&data);
+        data.len()
+    }
+
+    fn as_bytes(buffer: &[u64], len: usize) -> &[u8] {
+        unsafe { slice::from_raw_parts(buffer.as_ptr().cast(), len) }
+    }
+
+    #[test]
+    fn borrow_events() {
+        let log = log(2);
+        let mut buffer = Vec::new();
+        let len = save_aligned(&log, &mut buffer);
+
+        let parsed = LogRef::parse(as_bytes(&buffer, len)).expect("failed to parse log");
+        assert_eq!(parsed.header, log.header);
+        assert_eq!(parsed.skills, log.skills);
+        assert_eq!(
+            parsed
+                .events
+                .iter()
+                .map(|event| event.time)
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        let parsed = LogRef::parse_or_copy(as_bytes(&buffer, len)).expect("failed to parse log");
+        assert!(matches!(parsed.events, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut buffer = Vec::new();
+        let len = save_aligned(&log(1), &mut buffer);
+
+        let data = as_bytes(&buffer, len);
+
+        let result = LogRef::parse(data);
+        assert!(matches!(result, Err(ParseError::Unaligned(_))));
+
+        let parsed = LogRef::parse_or_copy(data).expect("failed to parse log");
+        assert!(matches!(parsed.events, Cow::Owned(_)));
+        assert_eq!(
+            parsed
+                .events
+                .iter()
+                .map(|event| event.time)
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let mut buffer = Vec::new();
+        let len = save_aligned(&log(2), &mut buffer);
+        let data = as_bytes(&buffer, len - 1);
+
+        let (parsed, truncation) =
+            LogRef::parse_with_truncation(data, ParseOptions::new()).expect("failed to parse log");
+        assert_eq!(parsed.events.len(), 2);
+        assert_eq!(
+            truncation,
+            Some(Truncation {
+                dangling_bytes: EVENT_SIZE - 1,
+                last_event: Some(1),
+            })
+        );
+
+        let result = LogRef::parse_with(data, ParseOptions::new().strict(true));
+        assert!(matches!(result, Err(ParseError::Truncated(_))));
+    }
+}
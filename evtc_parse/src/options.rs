@@ -0,0 +1,114 @@
+use crate::StateChange;
+use std::collections::HashSet;
+
+/// Options for parsing EVTC logs.
+///
+/// Allows selective parsing of logs, for example to only read the [`Header`](crate::Header) and [`Agent`](crate::Agent)s.
+///
+/// ```no_run
+/// use evtc_parse::{Log, ParseOptions};
+///
+/// let options = ParseOptions::new().skip_skills(true).skip_events(true);
+/// match Log::parse_file_with("path/to/log.evtc", options) {
+///     Ok(log) => println!("Log with {} agents", log.agents.len()),
+///     Err(err) => eprintln!("Encountered error {}", err),
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) strict: bool,
+    pub(crate) skip_skills: bool,
+    pub(crate) skip_events: bool,
+    pub(crate) max_events: Option<usize>,
+    pub(crate) statechanges: Option<HashSet<StateChange>>,
+    pub(crate) string_decoding: StringDecoding,
+}
+
+impl ParseOptions {
+    /// Creates new default parse options.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether parsing fails on a truncated log.
+    ///
+    /// By default a truncated log is parsed up to the last complete event
+    /// and the truncation is reported by [`LogReader::truncation`](crate::LogReader::truncation).
+    /// In strict mode [`ParseError::Truncated`](crate::ParseError::Truncated) is returned instead.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets whether to skip reading skill names.
+    ///
+    /// Skipped skills result in an empty list of [`Skill`](crate::Skill)s.
+    #[inline]
+    pub fn skip_skills(mut self, skip: bool) -> Self {
+        self.skip_skills = skip;
+        self
+    }
+
+    /// Sets whether to skip reading events.
+    ///
+    /// Skipped events result in an empty list of [`Event`](crate::Event)s.
+    /// Truncation of the log is not detected when skipping events.
+    #[inline]
+    pub fn skip_events(mut self, skip: bool) -> Self {
+        self.skip_events = skip;
+        self
+    }
+
+    /// Sets a maximum amount of events to read.
+    ///
+    /// Reading stops after the given amount of events passed the [`statechanges`](Self::statechanges) filter.
+    #[inline]
+    pub fn max_events(mut self, max: usize) -> Self {
+        self.max_events = Some(max);
+        self
+    }
+
+    /// Sets the [`StateChange`]s of events to keep.
+    ///
+    /// Events with other [`StateChange`]s are discarded while reading.
+    #[inline]
+    pub fn statechanges(mut self, statechanges: impl IntoIterator<Item = StateChange>) -> Self {
+        self.statechanges = Some(statechanges.into_iter().collect());
+        self
+    }
+
+    /// Sets how invalid UTF-8 in [`Agent`](crate::Agent) and [`Skill`](crate::Skill) names is handled.
+    ///
+    /// By default invalid names result in [`ParseError::FromUtf8Error`](crate::ParseError::FromUtf8Error).
+    #[inline]
+    pub fn string_decoding(mut self, decoding: StringDecoding) -> Self {
+        self.string_decoding = decoding;
+        self
+    }
+
+    /// Checks whether the given [`StateChange`] passes the filter.
+    #[inline]
+    pub(crate) fn keep_statechange(&self, statechange: StateChange) -> bool {
+        self.statechanges
+            .as_ref()
+            .is_none_or(|statechanges| statechanges.contains(&statechange))
+    }
+}
+
+/// Handling of invalid UTF-8 in names.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringDecoding {
+    /// Fail parsing on invalid UTF-8.
+    #[default]
+    Strict,
+
+    /// Truncate the name at the last valid char boundary.
+    ///
+    /// This recovers names cut off in the middle of a multi-byte character.
+    Truncate,
+
+    /// Replace invalid UTF-8 with [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    Lossy,
+}
@@ -0,0 +1,344 @@
+use crate::{
+    Agent, Header, Log, Parse, ParseError, ParseOptions, Skill, Truncation,
+    ext::event::parse_revision0,
+    util::{EVENT_SIZE, Endian, read_buffer_partial, skip_bytes},
+};
+use byteorder::ReadBytesExt;
+use evtc::Event;
+use std::io;
+
+/// A streaming reader for an EVTC log.
+///
+/// The [`Header`], [`Agent`]s and [`Skill`]s are read up front.
+/// Events are read lazily one at a time via the [`Iterator`] implementation,
+/// allowing to process large logs without holding every [`Event`] in memory.
+///
+/// Logs in the legacy EVTC revision 0 are converted to the current [`Event`] layout.
+///
+/// A truncated log ends iteration after the last complete event and reports the truncation via [`LogReader::truncation`].
+/// In strict mode the truncation is instead yielded as [`ParseError::Truncated`].
+///
+/// ```no_run
+/// use evtc_parse::LogReader;
+/// use std::{fs::File, io::BufReader};
+///
+/// let file = BufReader::new(File::open("path/to/log.evtc").unwrap());
+/// let mut reader = LogReader::new(file).expect("failed to parse log");
+/// println!("Log for boss id {}", reader.header.boss_id);
+///
+/// let count = reader.by_ref().filter_map(Result::ok).count();
+/// println!("Log has {count} events");
+/// ```
+#[derive(Debug)]
+pub struct LogReader<R> {
+    /// The log header with meta information.
+    pub header: Header,
+
+    /// Agents (entities) present in the log.
+    pub agents: Vec<Agent>,
+
+    /// Information about skills used in the log.
+    pub skills: Vec<Skill>,
+
+    /// Input to read events from.
+    input: R,
+
+    /// Options used for parsing.
+    options: ParseOptions,
+
+    /// Amount of events passing the filter.
+    kept: usize,
+
+    /// Truncation encountered while reading events.
+    truncation: Option<Truncation>,
+
+    /// Whether reading events has finished.
+    done: bool,
+}
+
+impl<R> LogReader<R>
+where
+    R: io::Read,
+{
+    /// Creates a new reader, reading header, agents and skills from the input.
+    pub fn new(input: R) -> Result<Self, ParseError> {
+        Self::with_options(input, ParseOptions::default())
+    }
+
+    /// Creates a new reader with the given [`ParseOptions`], reading header, agents and skills from the input.
+    pub fn with_options(mut input: R, options: ParseOptions) -> Result<Self, ParseError> {
+        let header = Header::parse(&mut input)?;
+
+        // we support current and legacy revision
+        if header.revision > Header::REVISION {
+            return Err(ParseError::UnsupportedRevision(header.revision));
+        }
+
+        let agent_count = input.read_u32::<Endian>()?;
+        let agents = (0..agent_count)
+            .map(|_| Agent::parse_with(&mut input, options.string_decoding))
+            .collect::<Result<_, _>>()?;
+
+        let skills = if !options.skip_skills {
+            let skill_count = input.read_u32::<Endian>()?;
+            (0..skill_count)
+                .map(|_| Skill::parse_with(&mut input, options.string_decoding))
+                .collect::<Result<_, _>>()?
+        } else {
+            // skill table only needs to be skipped when reading events after
+            if !options.skip_events {
+                let skill_count = input.read_u32::<Endian>()?;
+                skip_bytes(&mut input, skill_count as u64 * Skill::SIZE as u64)?;
+            }
+            Vec::new()
+        };
+
+        let done = options.skip_events || options.max_events == Some(0);
+
+        Ok(Self {
+            header,
+            agents,
+            skills,
+            input,
+            options,
+            kept: 0,
+            truncation: None,
+            done,
+        })
+    }
+
+    /// Returns the truncation encountered while reading events, if any.
+    ///
+    /// This is only known after all events have been read.
+    #[inline]
+    pub fn truncation(&self) -> Option<&Truncation> {
+        self.truncation.as_ref()
+    }
+
+    /// Reads all remaining events and converts the reader into a [`Log`].
+    #[inline]
+    pub fn into_log(self) -> Result<Log, ParseError> {
+        self.into_log_with_truncation().map(|(log, _)| log)
+    }
+
+    /// Reads all remaining events and converts the reader into a [`Log`],
+    /// returning the [`Truncation`] if the log ended with an incomplete event.
+    pub fn into_log_with_truncation(mut self) -> Result<(Log, Option<Truncation>), ParseError> {
+        let events = self.by_ref().collect::<Result<_, _>>()?;
+        let log = Log {
+            header: self.header,
+            agents: self.agents,
+            skills: self.skills,
+            events,
+        };
+        Ok((log, self.truncation))
+    }
+
+    /// Reads the next event from the input.
+    fn read_event(&mut self) -> Option<Result<Event, ParseError>> {
+        let mut buffer = [0; EVENT_SIZE];
+        match read_buffer_partial(&mut self.input, &mut buffer) {
+            Ok(EVENT_SIZE) => {
+                let event = if self.header.is_revision0() {
+                    parse_revision0(&mut buffer.as_slice())
+                } else {
+                    Event::parse(&mut buffer.as_slice())
+                };
+                Some(event.map_err(Into::into))
+            }
+            Ok(0) => None,
+            Ok(dangling_bytes) => {
+                let truncation = Truncation {
+                    dangling_bytes,
+                    last_event: self.kept.checked_sub(1),
+                };
+                if self.options.strict {
+                    Some(Err(ParseError::Truncated(truncation)))
+                } else {
+                    self.truncation = Some(truncation);
+                    None
+                }
+            }
+            Err(err) => Some(Err(err.into())),
+        }
+    }
+
+    /// Returns the underlying input.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+}
+
+impl<R> Iterator for LogReader<R>
+where
+    R: io::Read,
+{
+    type Item = Result<Event, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let event = match self.read_event() {
+                Some(Ok(event)) => event,
+                other => {
+                    self.done = true;
+                    return other;
+                }
+            };
+
+            if self.options.keep_statechange(event.get_statechange()) {
+                self.kept += 1;
+                if self.options.max_events == Some(self.kept) {
+                    self.done = true;
+                }
+                return Some(Ok(event));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Save, StateChange, fixture};
+
+    fn log() -> Log {
+        let events = (0..3)
+            .map(|time| fixture::statechange(time, 0, StateChange::Position))
+            .collect();
+        Log {
+            skills: vec![Skill {
+                id: 7,
+                name: "Skill Name".into(),
+            }],
+            ..fixture::log(123, Vec::new(), events)
+        }
+    }
+
+    fn save(log: &Log) -> Vec<u8> {
+        let mut data = Vec::new();
+        log.save(&mut data).expect("failed to save log");
+        data
+    }
+
+    #[test]
+    fn stream_events() {
+        let log = log();
+        let data = save(&log);
+
+        let mut reader = LogReader::new(data.as_slice()).expect("failed to read log");
+        assert_eq!(reader.header, log.header);
+        assert_eq!(reader.skills, log.skills);
+
+        let times = reader
+            .by_ref()
+            .map(|event| event.expect("failed to read event").time)
+            .collect::<Vec<_>>();
+        assert_eq!(times, [0, 1, 2]);
+        assert!(reader.next().is_none(), "reader not done");
+        assert_eq!(reader.truncation(), None);
+    }
+
+    #[test]
+    fn revision0() {
+        let mut log = log();
+        log.header.revision = 0;
+        log.events[1].skill_id = 5492;
+        log.events[1].src_master_instance_id = 3;
+        let data = save(&log);
+        assert_eq!(data.len(), save(&self::log()).len());
+
+        let parsed = Log::parse(&mut data.as_slice()).expect("failed to parse log");
+        assert_eq!(parsed.header, log.header);
+        assert_eq!(parsed.events.len(), 3);
+        assert_eq!(parsed.events[1].skill_id, 5492);
+        assert_eq!(parsed.events[1].src_master_instance_id, 3);
+        assert_eq!(parsed.events[1].get_statechange(), StateChange::Position);
+        assert_eq!(save(&parsed), data);
+
+        log.header.revision = 2;
+        let result = Log::parse(&mut save(&log).as_slice());
+        assert!(matches!(result, Err(ParseError::UnsupportedRevision(2))));
+    }
+
+    #[test]
+    fn options() {
+        let mut log = log();
+        log.events[1].is_statechange = StateChange::Velocity.into();
+        let data = save(&log);
+
+        let options = ParseOptions::new()
+            .skip_skills(true)
+            .statechanges([StateChange::Position])
+            .max_events(1);
+        let parsed = Log::parse_with(data.as_slice(), options).expect("failed to parse log");
+        assert!(parsed.skills.is_empty());
+        assert_eq!(
+            parsed
+                .events
+                .iter()
+                .map(|event| event.time)
+                .collect::<Vec<_>>(),
+            [0]
+        );
+
+        let options = ParseOptions::new().statechanges([StateChange::Position]);
+        let parsed = Log::parse_with(data.as_slice(), options).expect("failed to parse log");
+        assert_eq!(parsed.skills, log.skills);
+        assert_eq!(
+            parsed
+                .events
+                .iter()
+                .map(|event| event.time)
+                .collect::<Vec<_>>(),
+            [0, 2]
+        );
+
+        let options = ParseOptions::new().skip_skills(true).skip_events(true);
+        let parsed = Log::parse_with(data.as_slice(), options).expect("failed to parse log");
+        assert_eq!(parsed.header, log.header);
+        assert!(parsed.skills.is_empty());
+        assert!(parsed.events.is_empty());
+    }
+
+    #[test]
+    fn truncated() {
+        let mut log = log();
+        log.events[0].is_statechange = StateChange::Velocity.into();
+        let mut data = save(&log);
+        data.truncate(data.len() - 10);
+
+        let (log, truncation) = LogReader::new(data.as_slice())
+            .and_then(LogReader::into_log_with_truncation)
+            .expect("failed to parse log");
+        assert_eq!(log.events.len(), 2);
+        assert_eq!(
+            truncation,
+            Some(Truncation {
+                dangling_bytes: EVENT_SIZE - 10,
+                last_event: Some(1),
+            })
+        );
+
+        // index refers to the returned events
+        let options = ParseOptions::new().statechanges([StateChange::Position]);
+        let (log, truncation) = LogReader::with_options(data.as_slice(), options)
+            .and_then(LogReader::into_log_with_truncation)
+            .expect("failed to parse log");
+        assert_eq!(log.events.len(), 1);
+        assert_eq!(
+            truncation.and_then(|truncation| truncation.last_event),
+            Some(0)
+        );
+
+        let result = Log::parse_with(data.as_slice(), ParseOptions::new().strict(true));
+        assert!(matches!(
+            result,
+            Err(ParseError::Truncated(Truncation {
+                last_event: Some(1),
+                ..
+            }))
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    Parse, ParseError, Save,
-    util::{Endian, read_string_buffer, truncate_null, write_string_buffer},
+    Parse, ParseError, Save, StringDecoding,
+    util::{Endian, decode_string, read_buffer, write_string_buffer},
 };
 use byteorder::{ReadBytesExt, WriteBytesExt};
 use std::io;
@@ -22,16 +22,31 @@ pub struct Skill {
 impl Skill {
     /// Size of the skill name string.
     pub const NAME_SIZE: usize = 64;
+
+    /// Size of a skill in the EVTC file format.
+    pub const SIZE: usize = size_of::<u32>() + Self::NAME_SIZE;
+
+    /// Parses a skill from the input with the given [`StringDecoding`] for the name.
+    pub fn parse_with(
+        input: &mut impl io::Read,
+        decoding: StringDecoding,
+    ) -> Result<Self, ParseError> {
+        let id = input.read_u32::<Endian>()?;
+        let buffer = read_buffer::<{ Self::NAME_SIZE }>(input)?;
+        let end = buffer
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(buffer.len());
+        let name = decode_string(&buffer[..end], decoding)?;
+        Ok(Self { id, name })
+    }
 }
 
 impl Parse for Skill {
     type Error = ParseError;
 
     fn parse(input: &mut impl io::Read) -> Result<Self, Self::Error> {
-        Ok(Self {
-            id: input.read_u32::<Endian>()?,
-            name: truncate_null(read_string_buffer::<{ Self::NAME_SIZE }>(input)?),
-        })
+        Self::parse_with(input, StringDecoding::Strict)
     }
 }
 
@@ -66,4 +81,18 @@ mod tests {
             .expect("failed to save skill");
         assert_eq!(data, &buffer, "incorrect saved data");
     }
+
+    #[test]
+    fn skill_name_cut_off() {
+        let mut data = [0; Skill::SIZE];
+        data[4..].fill(b'a');
+        data[Skill::SIZE - 1] = 0xe2;
+
+        let result = Skill::parse_with(&mut data.as_slice(), StringDecoding::Strict);
+        assert!(matches!(result, Err(ParseError::FromUtf8Error(_))));
+
+        let skill = Skill::parse_with(&mut data.as_slice(), StringDecoding::Truncate)
+            .expect("failed to parse skill");
+        assert_eq!(skill.name, "a".repeat(Skill::NAME_SIZE - 1));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::ParseError;
+use crate::{ParseError, StringDecoding};
 use byteorder::LittleEndian;
 use std::io;
 
@@ -7,13 +7,8 @@ use std::io;
 /// EVTC logs will be written on Windows and Windows uses little endian.
 pub type Endian = LittleEndian;
 
-/// Truncates the string to remove trailing null character.
-pub fn truncate_null(mut string: String) -> String {
-    if let Some(end) = string.find('\0') {
-        string.truncate(end);
-    }
-    string
-}
+/// Size of an event in the EVTC file format.
+pub const EVENT_SIZE: usize = 64;
 
 /// Reads a fixed amount of bytes from the input into a buffer.
 pub fn read_buffer<const SIZE: usize>(input: &mut impl io::Read) -> io::Result<[u8; SIZE]> {
@@ -22,6 +17,32 @@ pub fn read_buffer<const SIZE: usize>(input: &mut impl io::Read) -> io::Result<[
     Ok(buffer)
 }
 
+/// Skips a fixed amount of bytes in the input.
+pub fn skip_bytes(input: &mut impl io::Read, count: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut io::Read::take(input, count), &mut io::sink())?;
+    if skipped == count {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+/// Reads bytes from the input into a buffer until it is full or the input ends.
+///
+/// Returns the amount of bytes read.
+pub fn read_buffer_partial(input: &mut impl io::Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buffer.len() {
+        match input.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(len) => read += len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(read)
+}
+
 /// Reads a UTF-8 string from a char buffer.
 pub fn read_string_buffer<const SIZE: usize>(
     input: &mut impl io::Read,
@@ -30,7 +51,22 @@ pub fn read_string_buffer<const SIZE: usize>(
     Ok(String::from_utf8(buffer.to_vec())?)
 }
 
-/// Reads a UTF-8 string from a char buffer.
+/// Decodes a UTF-8 string from bytes with the given [`StringDecoding`].
+pub fn decode_string(bytes: &[u8], decoding: StringDecoding) -> Result<String, ParseError> {
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(string) => Ok(string),
+        Err(err) => match decoding {
+            StringDecoding::Strict => Err(err.into()),
+            StringDecoding::Truncate => {
+                let valid = err.utf8_error().valid_up_to();
+                Ok(String::from_utf8_lossy(&bytes[..valid]).into_owned())
+            }
+            StringDecoding::Lossy => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        },
+    }
+}
+
+/// Writes a UTF-8 string to a char buffer.
 pub fn write_string_buffer<const SIZE: usize>(
     output: &mut impl io::Write,
     string: impl AsRef<str>,
@@ -1,6 +1,10 @@
-use crate::{Log, Parse, ParseError};
+use crate::{Log, LogReader, ParseError, ParseOptions, Save};
 use std::io;
-use zip::{ZipArchive, result::ZipError};
+use zip::{
+    CompressionMethod, ZipWriter, read::ZipFile, result::ZipError, write::SimpleFileOptions,
+};
+
+pub use zip::ZipArchive;
 
 /// Parses a [`Log`] from a compressed `zevtc` input.
 pub fn parse_zevtc(input: impl io::Read + io::Seek) -> Result<Log, ParseError> {
@@ -10,9 +14,70 @@ pub fn parse_zevtc(input: impl io::Read + io::Seek) -> Result<Log, ParseError> {
 impl Log {
     /// Parses a [`Log`] from a compressed `zevtc` input.
     pub fn parse_zevtc(input: impl io::Read + io::Seek) -> Result<Log, ParseError> {
-        let mut archive = ZipArchive::new(input).expect("input log file not compressed");
-        let mut file = archive.by_index(0).expect("input log file empty");
-        Log::parse(&mut file)
+        Self::parse_zevtc_with(input, ParseOptions::default())
+    }
+
+    /// Parses a [`Log`] from a compressed `zevtc` input with the given [`ParseOptions`].
+    pub fn parse_zevtc_with(
+        input: impl io::Read + io::Seek,
+        options: ParseOptions,
+    ) -> Result<Log, ParseError> {
+        let mut archive = ZipArchive::new(input)?;
+        LogReader::from_zevtc_with(&mut archive, options)?.into_log()
+    }
+
+    /// Default name of the log file inside a compressed `zevtc` archive.
+    pub const ZEVTC_ENTRY_NAME: &str = "log.evtc";
+
+    /// Saves the log as compressed `zevtc` into the output.
+    pub fn save_zevtc(&self, output: impl io::Write + io::Seek) -> Result<(), io::Error> {
+        self.save_zevtc_named(output, Self::ZEVTC_ENTRY_NAME)
+    }
+
+    /// Saves the log as compressed `zevtc` with the given name for the log file inside the archive.
+    pub fn save_zevtc_named(
+        &self,
+        output: impl io::Write + io::Seek,
+        name: &str,
+    ) -> Result<(), io::Error> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut writer = ZipWriter::new(output);
+        writer.start_file(name, options)?;
+        self.save(&mut writer)?;
+        writer.finish()?.flush()
+    }
+}
+
+impl<'a, R> LogReader<ZipFile<'a, R>>
+where
+    R: io::Read + io::Seek,
+{
+    /// Creates a new reader for the log in a compressed `zevtc` archive.
+    ///
+    /// ```no_run
+    /// use evtc_parse::{LogReader, ZipArchive};
+    /// use std::{fs::File, io::BufReader};
+    ///
+    /// let file = BufReader::new(File::open("path/to/log.zevtc").unwrap());
+    /// let mut archive = ZipArchive::new(file).expect("input log file not compressed");
+    /// let reader = LogReader::from_zevtc(&mut archive).expect("failed to parse log");
+    /// ```
+    pub fn from_zevtc(archive: &'a mut ZipArchive<R>) -> Result<Self, ParseError> {
+        Self::from_zevtc_with(archive, ParseOptions::default())
+    }
+
+    /// Creates a new reader for the log in a compressed `zevtc` archive with the given [`ParseOptions`].
+    pub fn from_zevtc_with(
+        archive: &'a mut ZipArchive<R>,
+        options: ParseOptions,
+    ) -> Result<Self, ParseError> {
+        match archive.len() {
+            0 => return Err(ParseError::EmptyArchive),
+            1 => {}
+            len => return Err(ParseError::MultipleEntries(len)),
+        }
+        let file = archive.by_index(0)?;
+        Self::with_options(file, options)
     }
 }
 
@@ -20,7 +85,162 @@ impl From<ZipError> for ParseError {
     fn from(err: ZipError) -> Self {
         match err {
             ZipError::Io(io) => Self::IoError(io),
-            _ => Self::NotEvtc,
+            ZipError::CompressionMethodNotSupported(method) => Self::UnsupportedCompression(method),
+            ZipError::InvalidArchive(message) => Self::InvalidArchive(message.into()),
+            err => Self::InvalidArchive(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Skill, StateChange, fixture};
+    use std::{
+        fs,
+        io::{Cursor, Write},
+    };
+
+    fn log() -> Log {
+        let agents = vec![fixture::agent(1, &["Character", ":Account.1234", "1"])];
+        let events = (0..3)
+            .map(|time| fixture::statechange(time, 1, StateChange::Position))
+            .collect();
+        Log {
+            skills: vec![Skill {
+                id: 7,
+                name: "Skill Name".into(),
+            }],
+            ..fixture::log(123, agents, events)
+        }
+    }
+
+    fn evtc() -> Vec<u8> {
+        let log = log();
+        let mut data = Vec::new();
+        log.save(&mut data).expect("failed to save log");
+        data
+    }
+
+    fn archive(entries: &[&str], options: SimpleFileOptions) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for name in entries {
+            writer
+                .start_file(*name, options)
+                .expect("failed to start file");
+            writer.write_all(&evtc()).expect("failed to write file");
+        }
+        writer
+            .finish()
+            .expect("failed to finish archive")
+            .into_inner()
+    }
+
+    #[test]
+    fn valid() {
+        let data = archive(&["log.evtc"], SimpleFileOptions::default());
+        let log = Log::parse_zevtc(Cursor::new(data)).expect("failed to parse log");
+        assert_eq!(log.header.boss_id, 123);
+    }
+
+    #[test]
+    fn not_compressed() {
+        let result = Log::parse_zevtc(Cursor::new(evtc()));
+        assert!(matches!(result, Err(ParseError::InvalidArchive(_))));
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = archive(&["log.evtc"], SimpleFileOptions::default());
+        data.truncate(data.len() / 2);
+        let result = Log::parse_zevtc(Cursor::new(data));
+        assert!(matches!(result, Err(ParseError::InvalidArchive(_))));
+    }
+
+    #[test]
+    fn empty() {
+        let data = archive(&[], SimpleFileOptions::default());
+        let result = Log::parse_zevtc(Cursor::new(data));
+        assert!(matches!(result, Err(ParseError::EmptyArchive)));
+    }
+
+    #[test]
+    fn file_not_found() {
+        let err = ParseError::from(ZipError::FileNotFound);
+        assert!(matches!(err, ParseError::InvalidArchive(_)));
+    }
+
+    #[test]
+    fn multiple_entries() {
+        let data = archive(&["a.evtc", "b.evtc"], SimpleFileOptions::default());
+        let result = Log::parse_zevtc(Cursor::new(data));
+        assert!(matches!(result, Err(ParseError::MultipleEntries(2))));
+    }
+
+    #[test]
+    fn unsupported_compression() {
+        const METHOD: u16 = 66;
+
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        let mut data = archive(&["log.evtc"], options);
+
+        // patch compression method in local file header and central directory
+        data[8..10].copy_from_slice(&METHOD.to_le_bytes());
+        let central = data
+            .windows(4)
+            .position(|window| window == b"PK\x01\x02")
+            .expect("missing central directory");
+        data[central + 10..central + 12].copy_from_slice(&METHOD.to_le_bytes());
+
+        let result = Log::parse_zevtc(Cursor::new(data));
+        assert!(
+            matches!(result, Err(ParseError::UnsupportedCompression(METHOD))),
+            "unexpected result {result:?}"
+        );
+    }
+
+    #[test]
+    fn corrupted_data() {
+        let mut data = archive(&["log.evtc"], SimpleFileOptions::default());
+        for byte in &mut data[30..40] {
+            *byte = !*byte;
         }
+        assert!(Log::parse_zevtc(Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn save_roundtrip() {
+        let log = log();
+        let mut output = Cursor::new(Vec::new());
+        log.save_zevtc(&mut output).expect("failed to save log");
+        output.set_position(0);
+
+        let parsed = Log::parse_zevtc(output).expect("failed to parse log");
+        assert_eq!(parsed.header, log.header);
+        assert_eq!(parsed.agents, log.agents);
+        assert_eq!(parsed.skills, log.skills);
+
+        let mut saved = Vec::new();
+        parsed.save(&mut saved).expect("failed to save log");
+        assert_eq!(saved, evtc());
+    }
+
+    #[test]
+    fn save_file_roundtrip() {
+        let path = std::env::temp_dir().join(format!("evtc_parse_{}.zevtc", std::process::id()));
+        log().save_file(&path).expect("failed to save log");
+
+        let archive = ZipArchive::new(fs::File::open(&path).expect("failed to open file"))
+            .expect("saved file not compressed");
+        let parsed = Log::parse_file(&path).expect("failed to parse log");
+        fs::remove_file(&path).expect("failed to remove file");
+
+        assert_eq!(
+            archive.file_names().collect::<Vec<_>>(),
+            [&format!("evtc_parse_{}.evtc", std::process::id())]
+        );
+        assert_eq!(parsed.header, log().header);
+        assert_eq!(parsed.events.len(), 3);
     }
 }